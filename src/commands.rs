@@ -1,14 +1,26 @@
-use std::fs::{self, File};
-use std::io::{self, Write};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
-use dialoguer::{theme::ColorfulTheme, Select};
-use dirs::home_dir;
+use dialoguer::{theme::ColorfulTheme, Confirm, Editor, Select};
+use serde::Serialize;
 
-use crate::project::{ProjectDetector, ProjectContext};
+use crate::project::{DetectionCandidate, FullStackLayout, ProjectContext, ProjectDetector, ProjectStructure, ProjectType, LOW_CONFIDENCE_THRESHOLD};
+use crate::smart_templates::{SmartTemplateManager, TemplateSet, TemplateSource};
 use crate::templates::TemplateManager;
-use crate::smart_templates::SmartTemplateManager;
-use crate::config::ConfigManager;
+use crate::config::{claude_dir, parse_frontmatter, Config, ConfigManager, MigrationOutcome, TemplateMetadata, CONFIG_VERSION};
+use crate::docs::{self, DocIssue, DocReport};
+use crate::error::CcmgenError;
+use crate::fsutil::{backup_file, write_atomic};
+use crate::git;
+use crate::journal::Journal;
+use crate::lock::CommandLock;
+use crate::t;
 
 /// 言語ごとのテンプレート定義
 fn get_language_templates() -> Vec<(&'static str, Vec<(&'static str, &'static str)>)> {
@@ -34,154 +46,2535 @@ fn get_language_templates() -> Vec<(&'static str, Vec<(&'static str, &'static st
     ]
 }
 
-/// コマンドを~/.claude/commandsに保存
-fn save_command(name: &str, body: &str) -> io::Result<()> {
-    let path = get_command_dir().join(format!("{name}.md"));
-    let mut file = File::create(path)?;
-    writeln!(file, "{}", body)?;
-    Ok(())
+/// `--lang`で指定できる言語
+pub const LANGUAGES: &[&str] = &["rust", "rust-wasm", "javascript", "typescript", "nodejs", "bun", "unity", "r", "haskell", "scala", "python"];
+
+/// `--lang`の値を正規の言語名に変換（`ts` → `typescript` などの別名に対応）
+pub fn resolve_language(input: &str) -> Option<&'static str> {
+    let input = input.trim().to_lowercase();
+    let canonical = match input.as_str() {
+        "rs" | "rust-normal" => "rust",
+        "wasm" => "rust-wasm",
+        "js" => "javascript",
+        "ts" => "typescript",
+        "node" | "node.js" | "node-js" => "nodejs",
+        "hs" => "haskell",
+        "sbt" => "scala",
+        "py" => "python",
+        other => other,
+    };
+    LANGUAGES.iter().copied().find(|lang| *lang == canonical)
+}
+
+/// 設定ファイル・`{lang}`で使う言語キー（`rust-wasm`は`rust`）
+fn language_key(lang: &str) -> String {
+    ProjectType::from_language_key(lang)
+        .map(|project_type| project_type.language_key().to_string())
+        .unwrap_or_else(|| lang.to_string())
+}
+
+/// 言語のテンプレート。旧来の定義があればそれを、なければ種別毎の組み込みテンプレートを使う
+fn templates_for_language(lang: &str) -> Vec<(String, String)> {
+    let to_owned = |templates: &[(&str, &str)]| -> Vec<(String, String)> {
+        templates.iter().map(|(name, content)| (name.to_string(), content.to_string())).collect()
+    };
+
+    match get_language_templates().iter().find(|(l, _)| *l == lang) {
+        Some((_, templates)) => to_owned(templates),
+        None => ProjectType::from_language_key(lang)
+            .map(|project_type| to_owned(&TemplateManager::get_templates_for_type(&project_type)))
+            .unwrap_or_default(),
+    }
+}
+
+/// コマンド保存の結果
+enum SaveOutcome {
+    Created,
+    /// `backup`は`--backup-existing`で退避した元のファイル
+    Updated { previous_content: String, backup: Option<PathBuf> },
+    Unchanged,
+}
+
+/// `--commands-ext`で指定したコマンドファイルの拡張子（未指定なら設定ファイルの`commands_ext`）
+static COMMANDS_EXT: OnceLock<String> = OnceLock::new();
+
+/// コマンドファイルの拡張子を設定する（先頭の`.`は省略可、空文字なら拡張子なし）
+pub fn set_commands_ext(ext: &str) {
+    let _ = COMMANDS_EXT.set(ext.trim_start_matches('.').to_string());
+}
+
+/// コマンドファイルの拡張子（既定は`md`、空文字なら拡張子なし）
+fn commands_ext() -> &'static str {
+    COMMANDS_EXT.get_or_init(|| {
+        ConfigManager::load_config()
+            .commands_ext
+            .map(|ext| ext.trim_start_matches('.').to_string())
+            .unwrap_or_else(|| "md".to_string())
+    })
+}
+
+/// コマンド名に対応するファイル名（`<name>.md`など）
+fn command_file_name(name: &str) -> String {
+    match commands_ext() {
+        "" => name.to_string(),
+        ext => format!("{name}.{ext}"),
+    }
+}
+
+/// エディタで開く一時ファイルの拡張子（`.md`など。拡張子なしなら空）
+fn editor_extension() -> String {
+    match commands_ext() {
+        "" => String::new(),
+        ext => format!(".{ext}"),
+    }
+}
+
+/// `init --backup-existing`で上書きする前に既存のコマンドを`<name>.md.bak`（既にあれば`.bak.1`など）に退避するか
+static BACKUP_EXISTING: AtomicBool = AtomicBool::new(false);
+
+/// 以降の`init`で上書きするコマンドを`<name>.md.bak`に退避する
+pub fn enable_backups() {
+    BACKUP_EXISTING.store(true, Ordering::Relaxed);
+}
+
+/// コマンドを`dir`（通常は~/.claude/commands）に保存
+///
+/// 既存ファイルの内容が同一であれば書き込みを行わない
+fn save_command(dir: &Path, name: &str, body: &str) -> io::Result<SaveOutcome> {
+    let path = dir.join(command_file_name(name));
+    let existing = read_existing_command(&path)?;
+    if existing.as_deref() == Some(format!("{body}\n").as_str()) {
+        return Ok(SaveOutcome::Unchanged);
+    }
+
+    // 退避に失敗した場合は上書きしない（拡張子が.bakなのでClaude Codeのコマンドとしては読み込まれない）
+    let backup = match &existing {
+        Some(_) if BACKUP_EXISTING.load(Ordering::Relaxed) => Some(backup_file(&path)?),
+        _ => None,
+    };
+
+    write_atomic(&path, &format!("{body}\n"))?;
+    Ok(match existing {
+        Some(previous_content) => SaveOutcome::Updated { previous_content, backup },
+        None => SaveOutcome::Created,
+    })
+}
+
+/// 既存のコマンドの内容（無ければNone）
+///
+/// 読めない・UTF-8でないファイルを無いものとして上書きしないよう、存在しない場合以外はエラーにする
+fn read_existing_command(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// 変更操作用のロックを取得。取得できなければエラーを表示してNoneを返す
+fn acquire_lock() -> Option<CommandLock> {
+    match CommandLock::acquire() {
+        Ok(lock) => Some(lock),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            None
+        }
+    }
+}
+
+/// 設定のエイリアスを解決したコマンド名。解決した場合はその旨を表示する
+fn resolve_command_name(name: &str) -> String {
+    let name = match ConfigManager::resolve_alias(name) {
+        Some(resolved) => {
+            eprintln!("🔗 エイリアス {} → {}", name, resolved);
+            resolved
+        }
+        None => name.to_string(),
+    };
+
+    if get_command_dir().is_ok_and(|dir| dir.join(command_file_name(&name)).exists()) {
+        return name;
+    }
+
+    // 設定の`name_prefix`付きで生成されたコマンド（例: rust-generate-tests）を名前だけで指定できるようにする
+    let Some(prefix) = ConfigManager::load_config().name_prefix else {
+        return name;
+    };
+    let prefixed: Vec<String> = command_files()
+        .iter()
+        .filter_map(|path| path.file_stem().map(|s| s.to_string_lossy().to_string()))
+        .filter(|stem| has_name_prefix(stem, &prefix, &name))
+        .collect();
+    match prefixed.as_slice() {
+        [single] => {
+            eprintln!("🔗 プレフィックス付きのコマンド {} → {}", name, single);
+            single.clone()
+        }
+        [] => name,
+        candidates => {
+            eprintln!("⚠️ {} に一致するプレフィックス付きのコマンドが複数あります: {}", name, candidates.join(", "));
+            name
+        }
+    }
+}
+
+/// `stem`が`name_prefix`を展開したプレフィックスと`name`からなるか
+///
+/// `{lang}`は対応言語（または空）、`{project}`は任意のプロジェクト名に一致する
+fn has_name_prefix(stem: &str, prefix: &str, name: &str) -> bool {
+    const PROJECT_MARKER: &str = "\0";
+    let Some(rest) = stem.strip_suffix(name) else {
+        return false;
+    };
+    LANGUAGES.iter().map(|lang| Some(*lang)).chain([None]).any(|lang| {
+        let expanded = substitute_name_prefix(prefix, lang, PROJECT_MARKER);
+        match expanded.split_once(PROJECT_MARKER) {
+            Some((before, after)) => rest
+                .strip_prefix(before)
+                .and_then(|project| project.strip_suffix(after))
+                .is_some_and(|project| !project.is_empty()),
+            None => rest == expanded,
+        }
+    })
+}
+
+/// `name_prefix`のプレースホルダ（`{lang}`、`{project}`）を展開し、区切りの`-`を補う
+///
+/// プロジェクト名はリポジトリ側で決まるため、英数字・`_`・`-`以外を`-`にしてから使う（`@acme/web` → `acme-web`）
+fn expand_name_prefix(prefix: &str, lang: Option<&str>, project: &str) -> String {
+    substitute_name_prefix(prefix, lang, &sanitize_name_part(project))
+}
+
+/// ファイル名の一部に使えるよう英数字・`_`・`-`以外を`-`にし、前後の`-`を除く（`..`や`/`を含めない）
+fn sanitize_name_part(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '_' | '-') { c } else { '-' })
+        .collect::<String>()
+        .trim_matches('-')
+        .to_string()
+}
+
+/// プレースホルダをそのまま置き換える（`has_name_prefix`の照合用）
+fn substitute_name_prefix(prefix: &str, lang: Option<&str>, project: &str) -> String {
+    let expanded = prefix
+        .replace("{lang}", lang.unwrap_or_default())
+        .replace("{project}", project);
+    if expanded.is_empty() || expanded.ends_with(['-', '_']) {
+        expanded
+    } else {
+        format!("{}-", expanded)
+    }
+}
+
+/// テンプレート名にプレフィックスを付ける
+fn apply_name_prefix(templates: Vec<(String, String)>, prefix: &str) -> Vec<(String, String)> {
+    templates
+        .into_iter()
+        .map(|(name, body)| (format!("{prefix}{name}"), body))
+        .collect()
+}
+
+/// ユーザーディレクトリのパス取得
+fn get_command_dir() -> Result<PathBuf, CcmgenError> {
+    Ok(claude_dir()?.join("commands"))
+}
+
+/// コマンドディレクトリを取得する。取得できなければエラーを表示してNone
+fn resolve_command_dir() -> Option<PathBuf> {
+    get_command_dir().inspect_err(|e| eprintln!("❌ {}", e)).ok()
+}
+
+/// `ccmgen detect`。プロジェクトを検出できなかった場合はfalseを返す
+pub fn detect(path: Option<String>, glob: Option<String>, explain: bool, json: bool) -> bool {
+    if let Some(pattern) = glob {
+        return detect_glob(&pattern, explain, json);
+    }
+    let target_path = path
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::current_dir().expect("カレントディレクトリの取得に失敗しました"));
+    detect_at(&target_path, explain, json)
+}
+
+/// `target_path`のプロジェクト情報を表示する。検出できなければfalse
+fn detect_at(target_path: &Path, explain: bool, json: bool) -> bool {
+    match ProjectDetector::detect_project(target_path) {
+        Some(project) if json => {
+            match serde_json::to_string_pretty(&project) {
+                Ok(json) => println!("{}", json),
+                Err(e) => eprintln!("❌ JSONへの変換に失敗しました: {}", e),
+            }
+            true
+        }
+        Some(project) => {
+            println!("{}", t!("detect.title"));
+            println!("{}", t!("detect.name", project.name));
+            if let Some(description) = &project.description {
+                println!("{}", t!("detect.description", description));
+            }
+            println!("{}", t!("detect.type", format!("{:?}", project.project_type)));
+            if let Some(framework) = &project.meta_framework {
+                println!("{}", t!("detect.framework", format!("{:?}", framework)));
+            }
+            if let Some(bundler) = &project.bundler {
+                println!("{}", t!("detect.bundler", format!("{:?}", bundler)));
+            }
+            println!("{}", t!("detect.path", project.path.display()));
+            if !project.features.is_empty() {
+                println!("{}", t!("detect.features", project.features.join(", ")));
+            }
+            print_candidates(&ProjectDetector::score_candidates(target_path));
+            if explain {
+                println!("{}", t!("detect.evidence"));
+                for reason in &project.evidence {
+                    println!("  - {}", reason);
+                }
+            }
+            true
+        }
+        None if json => {
+            print_json(&not_found_json(target_path));
+            false
+        }
+        None => {
+            println!("{}", t!("detect.not_found"));
+            false
+        }
+    }
+}
+
+/// `detect --glob`。一致した各ディレクトリを検出してまとめて表示し、全て検出できればtrue
+fn detect_glob(pattern: &str, explain: bool, json: bool) -> bool {
+    let Some(roots) = expand_glob_roots(pattern) else {
+        return false;
+    };
+
+    if json {
+        let mut all_detected = true;
+        let values: Vec<serde_json::Value> = roots
+            .iter()
+            .map(|root| match ProjectDetector::detect_project(root) {
+                Some(project) => serde_json::to_value(&project).unwrap_or_default(),
+                None => {
+                    all_detected = false;
+                    not_found_json(root)
+                }
+            })
+            .collect();
+        print_json(&serde_json::Value::Array(values));
+        return all_detected;
+    }
+
+    let mut detected = 0;
+    for root in &roots {
+        println!("\n📂 {}", root.display());
+        if detect_at(root, explain, false) {
+            detected += 1;
+        }
+    }
+    println!("\n📊 {} に一致する {} 個のディレクトリ中 {} 個でプロジェクトを検出しました", pattern, roots.len(), detected);
+    detected == roots.len()
+}
+
+/// `--glob`のパターンに一致するディレクトリを名前順に返す。パターンが不正・一致なしならエラーを表示してNone
+pub fn expand_glob_roots(pattern: &str) -> Option<Vec<PathBuf>> {
+    let paths = match glob::glob(pattern) {
+        Ok(paths) => paths,
+        Err(e) => {
+            eprintln!("❌ 不正なパターンです: {}: {}", pattern, e);
+            return None;
+        }
+    };
+    let mut roots: Vec<PathBuf> = paths.flatten().filter(|path| path.is_dir()).collect();
+    roots.sort();
+    if roots.is_empty() {
+        eprintln!("❓ {} に一致するディレクトリがありません", pattern);
+        return None;
+    }
+    Some(roots)
+}
+
+/// 検出候補の上位を確信度付きで表示
+fn print_candidates(candidates: &[DetectionCandidate]) {
+    if candidates.is_empty() {
+        return;
+    }
+    let summary: Vec<String> = candidates
+        .iter()
+        .take(3)
+        .map(|c| format!("{:?} {:.0}%", c.project_type, c.confidence * 100.0))
+        .collect();
+    println!("  候補: {}", summary.join(", "));
+}
+
+/// 検出の確信度が低い場合に種別の手動選択を促す
+fn confirm_low_confidence(context: ProjectContext, target_path: &Path) -> ProjectContext {
+    let candidates = ProjectDetector::score_candidates(target_path);
+    let confidence = candidates
+        .iter()
+        .find(|c| c.project_type == context.info.project_type)
+        .map(|c| c.confidence)
+        .unwrap_or(1.0);
+    if candidates.len() < 2 || confidence >= LOW_CONFIDENCE_THRESHOLD {
+        return context;
+    }
+
+    println!(
+        "⚠️ 検出の確信度が低いです: {:?} ({:.0}%)",
+        context.info.project_type,
+        confidence * 100.0
+    );
+    let items: Vec<String> = candidates
+        .iter()
+        .map(|c| format!("{:?} ({:.0}%)", c.project_type, c.confidence * 100.0))
+        .collect();
+    let default = candidates
+        .iter()
+        .position(|c| c.project_type == context.info.project_type)
+        .unwrap_or(0);
+    let selection = select("プロジェクト種別を選択してください", &items, default);
+
+    let selected = candidates[selection].project_type.clone();
+    if selected == context.info.project_type {
+        return context;
+    }
+    ProjectDetector::create_project_context_as(target_path, selected).unwrap_or(context)
+}
+
+/// `ccmgen init` のオプション
+#[derive(Debug, Default)]
+pub struct InitOptions {
+    pub lang: Option<String>,
+    pub repo: Option<String>,
+    pub path: Option<String>,
+    /// テンプレート毎に採用・スキップ・編集を選択する
+    pub interactive: bool,
+    /// 指定したテンプレートのみを生成する
+    pub template: Option<String>,
+    /// 生成後に`post_init_hook`を実行する
+    pub run_hooks: bool,
+    /// 自動検出せず設定ファイルの`[init] templates`を生成する
+    pub from_config: bool,
+    /// 生成するテンプレート数の上限（プロジェクト固有の提案を優先）
+    pub max_templates: Option<usize>,
+    /// `template`の既存コマンドに追記するテキスト
+    pub append: Option<String>,
+    /// 直下のサブディレクトリのプロジェクト毎に`.claude/commands`を生成するディレクトリ
+    pub recursive: Option<String>,
+    /// パターンに一致したディレクトリをそれぞれプロジェクトとして`.claude/commands`を生成する
+    pub glob: Option<String>,
+    /// `recursive`・`glob`で書き込まずに予定のみ表示する
+    pub dry_run: bool,
+    /// 生成するファイル名のプレフィックス（未指定なら設定ファイルの`name_prefix`）
+    pub name_prefix: Option<String>,
+    /// 生成するテンプレートをまとめたプリセット名
+    pub preset: Option<String>,
+    /// 検出したプロジェクトの推奨コマンドのみを生成する
+    pub only_suggested: bool,
+    /// `recursive`・`glob`のプロジェクトを並列に処理する場合のスレッド数
+    pub jobs: Option<usize>,
+    /// プロジェクトを検出できなければ言語を尋ねずに失敗する（`--strict`）
+    pub strict: bool,
+}
+
+/// `init --preset`の組み込みプリセット（`*`は検出したプロジェクトの全テンプレート）
+///
+/// 設定ファイルの`[presets]`で同名のプリセットを上書き・追加できる
+const PRESETS: &[(&str, &[&str])] = &[
+    ("minimal", &["generate-tests", "add-error-handling", "generate-commit-message", "summarize-diff"]),
+    (
+        "testing",
+        &["generate-tests", "run-specific-test", "test-coverage-analysis", "add-playmode-test", "generate-trybuild-test",
+            "convert-to-bun-test", "validate-against-spec"],
+    ),
+    (
+        "docs",
+        &["add-documentation", "add-jsdoc", "declaration-files", "readme-generator", "documentation-generator",
+            "add-doc-page", "review-doc-style", "generate-sidebar"],
+    ),
+    ("full", &["*"]),
+];
+
+/// プリセットのテンプレート名（設定ファイルの`[presets]`を優先）
+fn resolve_preset(config: &Config, name: &str) -> Option<Vec<String>> {
+    config.presets.get(name).cloned().or_else(|| {
+        PRESETS
+            .iter()
+            .find(|(preset, _)| *preset == name)
+            .map(|(_, templates)| templates.iter().map(|t| t.to_string()).collect())
+    })
+}
+
+/// 組み込みと設定ファイルのプリセット名
+fn preset_names(config: &Config) -> Vec<String> {
+    let mut names: Vec<String> = PRESETS.iter().map(|(name, _)| name.to_string()).collect();
+    let mut custom: Vec<&String> = config.presets.keys().filter(|name| !names.contains(name)).collect();
+    custom.sort();
+    names.extend(custom.into_iter().cloned());
+    names
+}
+
+/// `ccmgen init` コマンド。書き込みに失敗したファイルがあればfalseを返す
+pub fn init(options: InitOptions) -> bool {
+    let InitOptions {
+        lang, repo, path, interactive, template, run_hooks, from_config, max_templates, append, recursive, glob, dry_run,
+        name_prefix, preset, only_suggested, jobs, strict,
+    } = options;
+    let name_prefix = name_prefix.or_else(|| ConfigManager::load_config().name_prefix);
+
+    let preset = match preset {
+        Some(name) => {
+            let config = ConfigManager::load_config();
+            match resolve_preset(&config, &name) {
+                Some(templates) => Some((name, templates)),
+                None => {
+                    eprintln!("❌ プリセットが見つかりません: {}", name);
+                    eprintln!("   利用可能なプリセット: {}", preset_names(&config).join(", "));
+                    return false;
+                }
+            }
+        }
+        None => None,
+    };
+
+    if let Some(dir) = recursive {
+        return init_recursive(Path::new(&dir), dry_run, name_prefix.as_deref(), jobs);
+    }
+    if let Some(pattern) = glob {
+        let Some(roots) = expand_glob_roots(&pattern) else {
+            return false;
+        };
+        let scope = format!("{} に一致するディレクトリ", pattern);
+        return init_roots(&roots, Path::new(""), &scope, dry_run, name_prefix.as_deref(), jobs);
+    }
+
+    if let (Some(name), Some(text)) = (&template, &append) {
+        return append_to_command(name, text);
+    }
+
+    if let Some(repo_url) = repo {
+        println!("🔗 GitHubテンプレートのダウンロードは未実装です: {repo_url}");
+        // TODO: GitHub連携処理（git2またはreqwest+zip）
+        return true;
+    }
+
+    let target_path = path
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::current_dir().expect("カレントディレクトリの取得に失敗しました"));
+
+    if lang.is_none()
+        && !from_config
+        && ProjectDetector::detect_project(&target_path).is_none()
+        && ProjectDetector::detect_fullstack(&target_path).is_none()
+    {
+        if strict {
+            eprintln!("{}", t!("detect.not_found"));
+            eprintln!("   --strict のため終了します（言語を指定するには --lang）");
+            return false;
+        }
+        if !prompts_enabled() {
+            eprintln!("{}", t!("detect.not_found"));
+            eprintln!("   対話できないため言語を選択できません（--lang で指定してください）");
+            return false;
+        }
+    }
+
+    let prefix_lang = lang.as_deref().map(language_key);
+    let templates = if from_config {
+        resolve_configured_templates()
+    } else {
+        detect_init_templates(lang, &target_path, template, preset, only_suggested, max_templates.is_some())
+    };
+    let templates = match templates {
+        Ok(templates) => templates,
+        Err(InitStop::Cancelled) => return true,
+        Err(InitStop::Failed) => return false,
+    };
+    let templates = match max_templates {
+        Some(max) => cap_templates(templates, max),
+        None => templates,
+    };
+
+    let templates = match &name_prefix {
+        Some(prefix) => {
+            let detected = ProjectDetector::detect_project(&target_path);
+            let lang = prefix_lang.or_else(|| detected.as_ref().map(|i| i.project_type.language_key().to_string()));
+            let project = detected
+                .map(|i| i.name)
+                .unwrap_or_else(|| target_path.file_name().unwrap_or_default().to_string_lossy().to_string());
+            apply_name_prefix(templates, &expand_name_prefix(prefix, lang.as_deref(), &project))
+        }
+        None => templates,
+    };
+
+    let templates = if interactive {
+        review_templates(templates)
+    } else {
+        templates
+    };
+
+    let Some(cmd_dir) = resolve_command_dir() else {
+        return false;
+    };
+    // --interactive では1件ずつ確認済み。端末以外（パイプ・CI）では確認しない
+    if !interactive && prompts_enabled() && io::stdout().is_terminal() && !confirm_init_plan(&cmd_dir, &templates) {
+        println!("🚫 中止しました");
+        return true;
+    }
+
+    let Some(_lock) = acquire_lock() else {
+        return false;
+    };
+
+    if let Err(e) = fs::create_dir_all(&cmd_dir).map_err(CcmgenError::Io) {
+        eprintln!("❌ {} を作成できませんでした: {}", cmd_dir.display(), e);
+        return false;
+    }
+
+    let results: Vec<(String, io::Result<SaveOutcome>)> = templates
+        .into_iter()
+        .map(|(name, body)| {
+            let outcome = save_command(&cmd_dir, &name, &body);
+            (name, outcome)
+        })
+        .collect();
+
+    let mut journal = Journal::default();
+    let (mut created, mut updated, mut unchanged, mut failed) = (0, 0, 0, 0);
+    for (name, outcome) in results {
+        let path = cmd_dir.join(command_file_name(&name));
+        match outcome {
+            Ok(SaveOutcome::Created) => {
+                journal.record_created(path);
+                created += 1;
+                println!("{}", t!("init.created", command_file_name(&name)));
+            }
+            Ok(SaveOutcome::Updated { previous_content, backup }) => {
+                if let Some(backup) = &backup {
+                    println!("💾 {} を {} に退避しました", command_file_name(&name), backup.display());
+                }
+                journal.record_overwritten(path, previous_content, backup);
+                updated += 1;
+                println!("{}", t!("init.updated", command_file_name(&name)));
+            }
+            Ok(SaveOutcome::Unchanged) => {
+                unchanged += 1;
+                println!("{}", t!("init.unchanged", command_file_name(&name)));
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!("{}", t!("init.failed", command_file_name(&name), e));
+            }
+        }
+    }
+
+    if let Err(e) = journal.save_or_clear() {
+        eprintln!("⚠️ 取り消し用ジャーナルの保存に失敗しました: {}", e);
+    }
+
+    println!("{}", t!("init.summary", created, updated, unchanged, failed));
+    if failed > 0 {
+        eprintln!("{}", t!("init.partial"));
+    } else {
+        println!("{}", t!("init.done"));
+    }
+
+    if let Some(hook) = ConfigManager::load_config().post_init_hook {
+        if !run_hooks {
+            println!("💡 post_init_hook はスキップしました（実行するには --run-hooks を指定）");
+        } else if failed > 0 {
+            eprintln!("⚠️ 作成に失敗したファイルがあるため post_init_hook を実行しません");
+        } else {
+            run_post_init_hook(&hook, &cmd_dir);
+        }
+    }
+
+    failed == 0
+}
+
+/// `--yes`/`--no-input`で確認・選択をすべて既定値で済ませるか
+static NO_INPUT: AtomicBool = AtomicBool::new(false);
+
+/// 以降の確認・選択を表示せず既定値を使う
+pub fn disable_input() {
+    NO_INPUT.store(true, Ordering::Relaxed);
+}
+
+/// 確認・選択を表示できるか（`--yes`指定時・標準入力が端末でない場合は不可）
+fn prompts_enabled() -> bool {
+    !NO_INPUT.load(Ordering::Relaxed) && io::stdin().is_terminal()
+}
+
+/// はい/いいえの確認。対話できない場合は既定値を返す
+fn confirm(prompt: &str, default: bool) -> bool {
+    if !prompts_enabled() {
+        return default;
+    }
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .default(default)
+        .interact()
+        .unwrap_or(false)
+}
+
+/// 一覧からの選択。対話できない場合は既定の項目を返す
+fn select<T: ToString>(prompt: &str, items: &[T], default: usize) -> usize {
+    if !prompts_enabled() {
+        return default;
+    }
+    Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .items(items)
+        .default(default)
+        .interact()
+        .unwrap_or(default)
+}
+
+/// 書き込む予定のコマンド（既存ファイルには上書きの印）を表示し、続行するか確認する
+fn confirm_init_plan(cmd_dir: &Path, templates: &[(String, String)]) -> bool {
+    if templates.is_empty() {
+        return true;
+    }
+
+    println!("📝 {} に {} 個のコマンドを作成します:", cmd_dir.display(), templates.len());
+    for (name, _) in templates {
+        if cmd_dir.join(command_file_name(name)).exists() {
+            println!("  - {} （既存のファイルを更新）", name);
+        } else {
+            println!("  - {}", name);
+        }
+    }
+    confirm("続行しますか？", true)
+}
+
+/// `post_init_hook`をシェル経由で実行する。コマンドディレクトリは第1引数と`CCMGEN_COMMANDS_DIR`で渡す
+fn run_post_init_hook(hook: &str, cmd_dir: &Path) {
+    println!("🪝 post_init_hook を実行します: {}", hook);
+
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(hook).arg(cmd_dir);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(hook).arg("ccmgen-hook").arg(cmd_dir);
+        command
+    };
+
+    match command.env("CCMGEN_COMMANDS_DIR", cmd_dir).status() {
+        Ok(status) if status.success() => println!("✅ post_init_hook が完了しました"),
+        Ok(status) => eprintln!("⚠️ post_init_hook が失敗しました ({})", status),
+        Err(e) => eprintln!("⚠️ post_init_hook の起動に失敗しました: {}", e),
+    }
+}
+
+/// `init`で生成するテンプレートを決めずに終了する理由
+enum InitStop {
+    /// 生成するものが無い（終了コード0）
+    Cancelled,
+    /// テンプレートが見つからないなどのエラー（表示済み）
+    Failed,
+}
+
+/// プロジェクト検出（または`--lang`）に基づいて生成するテンプレートを決定
+///
+/// `prioritize_suggestions`が真ならプロジェクト固有の提案を先頭に並べる
+fn detect_init_templates(
+    lang: Option<String>,
+    target_path: &Path,
+    template: Option<String>,
+    preset: Option<(String, Vec<String>)>,
+    only_suggested: bool,
+    prioritize_suggestions: bool,
+) -> Result<Vec<(String, String)>, InitStop> {
+    // バックエンド・フロントエンドを持つ構成は領域毎に生成
+    if lang.is_none()
+        && let Some(layout) = ProjectDetector::detect_fullstack(target_path)
+    {
+        return fullstack_init_templates(&layout, template, preset, only_suggested);
+    }
+
+    // プロジェクト自動検出を試行
+    let project_context = if lang.is_none() {
+        ProjectDetector::create_project_context(target_path)
+            .map(|context| confirm_low_confidence(context, target_path))
+    } else {
+        None
+    };
+
+    let (language, templates) = if let Some(ref context) = project_context {
+        println!("{}", t!("init.detected", context.info.name, format!("{:?}", context.info.project_type)));
+        let mut templates = match SmartTemplateManager::create_enhanced_init_templates(context) {
+            Ok(templates) => templates,
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                return Err(InitStop::Failed);
+            }
+        };
+        // --no-context ではプロジェクト固有のコマンドは生成されない
+        let suggested_names = SmartTemplateManager::suggested_template_names(context);
+        let suggested = suggested_names.iter().filter(|c| templates.iter().any(|(n, _)| n == *c)).count();
+        println!("💡 {} 個のプロジェクト固有コマンドを含みます", suggested);
+        if only_suggested {
+            templates.retain(|(name, _)| suggested_names.contains(name));
+        }
+        if prioritize_suggestions {
+            templates.sort_by_key(|(name, _)| !suggested_names.contains(name));
+        }
+        (context.info.project_type.language_key().to_string(), templates)
+    } else {
+        if only_suggested {
+            eprintln!("{}", t!("detect.not_found"));
+            eprintln!("   --only-suggested は検出したプロジェクトの推奨コマンドを生成します");
+            return Err(InitStop::Failed);
+        }
+        // 手動選択または古いロジック
+        let selected_lang = match lang {
+            Some(lang) => lang,
+            None => {
+                let selection = select("言語を選択してください", LANGUAGES, 0);
+                LANGUAGES[selection].to_string()
+            }
+        };
+
+        let mut templates = TemplateSet::new(ConfigManager::load_config().conflict_policy);
+        templates.add(TemplateSource::Builtin, templates_for_language(&selected_lang));
+        let language = language_key(&selected_lang);
+        let custom_templates = SmartTemplateManager::render_custom_templates(&language)
+            .into_iter()
+            .map(|(name, _, content)| (name, content));
+        templates.add(TemplateSource::Custom, custom_templates);
+        match templates.into_templates() {
+            Ok(templates) => (language, templates),
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                return Err(InitStop::Failed);
+            }
+        }
+    };
+
+    let templates = match template {
+        // 明示的に指定されたテンプレートは無効化設定より優先
+        Some(template_name) => match templates.iter().find(|(name, _)| *name == template_name) {
+            Some(found) => vec![found.clone()],
+            None => {
+                let names: Vec<&str> = templates.iter().map(|(name, _)| name.as_str()).collect();
+                eprintln!("{}", t!("init.template_not_found", template_name));
+                eprintln!("   利用可能なテンプレート: {}", names.join(", "));
+                return Err(InitStop::Failed);
+            }
+        },
+        None => {
+            let config = ConfigManager::load_config();
+            let templates = templates
+                .into_iter()
+                .filter(|(name, _)| !ConfigManager::is_template_disabled(&config, name))
+                .collect();
+            match preset {
+                // プリセットは`[defaults]`の既定セットより優先
+                Some((preset_name, names)) => apply_preset(templates, &preset_name, &names)?,
+                None if only_suggested => templates,
+                None => apply_default_subset(templates, &config, &language),
+            }
+        }
+    };
+
+    if only_suggested && templates.is_empty() {
+        println!("ℹ️ このプロジェクトに固有の推奨コマンドはありません（ccmgen analyze で確認できます）");
+        return Err(InitStop::Cancelled);
+    }
+    Ok(templates)
+}
+
+/// フルスタック構成の各領域のコンテキストでテンプレートを作り、`backend-`・`frontend-`を付けて返す
+fn fullstack_init_templates(
+    layout: &FullStackLayout,
+    template: Option<String>,
+    preset: Option<(String, Vec<String>)>,
+    only_suggested: bool,
+) -> Result<Vec<(String, String)>, InitStop> {
+    let config = ConfigManager::load_config();
+    let mut templates = Vec::new();
+    for (area, context) in layout.areas() {
+        println!(
+            "🧩 {}: {} ({:?}, {})",
+            area,
+            context.info.name,
+            context.info.project_type,
+            context.info.path.display()
+        );
+        let mut area_templates = match SmartTemplateManager::create_enhanced_init_templates(context) {
+            Ok(templates) => templates,
+            Err(e) => {
+                eprintln!("❌ {}: {}", area, e);
+                return Err(InitStop::Failed);
+            }
+        };
+        if only_suggested {
+            let suggested_names = SmartTemplateManager::suggested_template_names(context);
+            area_templates.retain(|(name, _)| suggested_names.contains(name));
+        }
+        let area_templates: Vec<(String, String)> = match (&template, &preset) {
+            (Some(_), _) => area_templates,
+            (None, None) if only_suggested => area_templates,
+            (None, Some((_, names))) if names.iter().any(|name| name == "*") => area_templates,
+            (None, Some((_, names))) => area_templates.into_iter().filter(|(name, _)| names.contains(name)).collect(),
+            (None, None) => apply_default_subset(area_templates, &config, context.info.project_type.language_key()),
+        };
+        templates.extend(
+            area_templates
+                .into_iter()
+                .filter(|(name, _)| template.is_some() || !ConfigManager::is_template_disabled(&config, name))
+                .map(|(name, content)| (format!("{}-{}", area, name), content)),
+        );
+    }
+
+    if let Some(template_name) = template {
+        return match templates.iter().find(|(name, _)| *name == template_name) {
+            Some(found) => Ok(vec![found.clone()]),
+            None => {
+                let names: Vec<&str> = templates.iter().map(|(name, _)| name.as_str()).collect();
+                eprintln!("{}", t!("init.template_not_found", template_name));
+                eprintln!("   利用可能なテンプレート: {}", names.join(", "));
+                Err(InitStop::Failed)
+            }
+        };
+    }
+    if let Some((preset_name, _)) = &preset {
+        if templates.is_empty() {
+            eprintln!("❌ プリセット {} にこのプロジェクトで使えるテンプレートがありません", preset_name);
+            return Err(InitStop::Failed);
+        }
+        println!("📦 プリセット {} を適用します（{}個）", preset_name, templates.len());
+    }
+    Ok(templates)
+}
+
+/// プリセットに含まれるテンプレートに絞る。検出したプロジェクトに無いテンプレートは省略を案内する
+fn apply_preset(templates: Vec<(String, String)>, preset: &str, names: &[String]) -> Result<Vec<(String, String)>, InitStop> {
+    if names.iter().any(|name| name == "*") {
+        println!("📦 プリセット {} を適用します（{}個）", preset, templates.len());
+        return Ok(templates);
+    }
+
+    let skipped: Vec<&str> = names
+        .iter()
+        .filter(|name| !templates.iter().any(|(n, _)| n == *name))
+        .map(String::as_str)
+        .collect();
+    let templates: Vec<(String, String)> = templates.into_iter().filter(|(name, _)| names.contains(name)).collect();
+    if templates.is_empty() {
+        eprintln!("❌ プリセット {} にこのプロジェクトで使えるテンプレートがありません", preset);
+        return Err(InitStop::Failed);
+    }
+
+    println!("📦 プリセット {} を適用します（{}個）", preset, templates.len());
+    if !skipped.is_empty() {
+        println!("   このプロジェクトに該当しないため省略: {}", skipped.join(", "));
+    }
+    Ok(templates)
+}
+
+/// `[defaults.<language>]`が指定されていれば、組み込みテンプレートをその既定セットに絞る
+///
+/// プロジェクト固有の提案やカスタムテンプレートはそのまま残す
+fn apply_default_subset(templates: Vec<(String, String)>, config: &Config, language: &str) -> Vec<(String, String)> {
+    let Some(subset) = ConfigManager::default_template_subset(config, language) else {
+        return templates;
+    };
+
+    let builtin: Vec<String> = TemplateManager::all_builtin_templates()
+        .into_iter()
+        .map(|(name, _)| name.to_string())
+        .chain(templates_for_language(language).into_iter().map(|(name, _)| name))
+        .collect();
+    templates
+        .into_iter()
+        .filter(|(name, _)| !builtin.contains(name) || subset.contains(name))
+        .collect()
+}
+
+/// `init --recursive`・`init --glob`で処理したプロジェクト毎の結果
+struct RecursiveResult {
+    name: String,
+    project_type: String,
+    created: usize,
+    updated: usize,
+    unchanged: usize,
+    failed: usize,
+}
+
+/// `dir`直下の各プロジェクトに`.claude/commands`を生成
+/// 失敗したファイルがあればfalseを返す
+fn init_recursive(dir: &Path, dry_run: bool, name_prefix: Option<&str>, jobs: Option<usize>) -> bool {
+    let mut subdirs: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect(),
+        Err(e) => {
+            eprintln!("❌ ディレクトリを読み込めませんでした: {}: {}", dir.display(), e);
+            return false;
+        }
+    };
+    subdirs.sort();
+    init_roots(&subdirs, dir, &format!("{} 直下", dir.display()), dry_run, name_prefix, jobs)
+}
+
+/// `roots`の各プロジェクトに`.claude/commands`を生成する。表示名は`base`からの相対パス、`scope`はメッセージ用の説明
+/// 失敗したファイルがあればfalseを返す
+fn init_roots(
+    roots: &[PathBuf],
+    base: &Path,
+    scope: &str,
+    dry_run: bool,
+    name_prefix: Option<&str>,
+    jobs: Option<usize>,
+) -> bool {
+    // --dry-run は何も書き込まないので確認しない
+    if !dry_run && prompts_enabled() && io::stdout().is_terminal() {
+        let projects: Vec<String> = roots
+            .iter()
+            .filter(|root| ProjectDetector::detect_project(root).is_some())
+            .map(|root| root_display_name(root, base))
+            .collect();
+        if !projects.is_empty() {
+            println!(
+                "📝 {}の {} 個のプロジェクトに .claude/commands を生成します: {}",
+                scope,
+                projects.len(),
+                projects.join(", ")
+            );
+            let confirmed = confirm("続行しますか？", true);
+            if !confirmed {
+                println!("🚫 中止しました");
+                return true;
+            }
+        }
+    }
+
+    let _lock = if dry_run {
+        None
+    } else {
+        let Some(lock) = acquire_lock() else {
+            return false;
+        };
+        Some(lock)
+    };
+
+    let config = ConfigManager::load_config();
+    let outcomes: Vec<ProjectInitOutcome> = match jobs {
+        Some(jobs) => init_projects_parallel(roots, base, &config, dry_run, name_prefix, jobs),
+        None => roots
+            .iter()
+            .filter_map(|root| {
+                let outcome = init_project(root, base, &config, dry_run, name_prefix)?;
+                outcome.print_log();
+                Some(outcome)
+            })
+            .collect(),
+    };
+
+    if outcomes.is_empty() {
+        println!("❓ {}に対応するプロジェクトが見つかりませんでした", scope);
+        return true;
+    }
+
+    let mut journal = Journal::default();
+    let mut results = Vec::new();
+    for outcome in outcomes {
+        journal.extend(outcome.journal);
+        results.push(outcome.result);
+    }
+
+    if !dry_run
+        && let Err(e) = journal.save_or_clear()
+    {
+        eprintln!("⚠️ 取り消し用ジャーナルの保存に失敗しました: {}", e);
+    }
+
+    print_recursive_summary(&results, dry_run);
+    results.iter().all(|r| r.failed == 0)
+}
+
+/// 処理結果に表示するプロジェクト名（`base`からの相対パス）
+fn root_display_name(root: &Path, base: &Path) -> String {
+    root.strip_prefix(base).unwrap_or(root).display().to_string()
+}
+
+/// `init --recursive`の1プロジェクト分の結果。出力はまとめて表示するため溜めておく
+struct ProjectInitOutcome {
+    result: RecursiveResult,
+    journal: Journal,
+    /// (標準エラーか, 行)
+    log: Vec<(bool, String)>,
+}
+
+impl ProjectInitOutcome {
+    fn print_log(&self) {
+        for (is_error, line) in &self.log {
+            if *is_error {
+                eprintln!("{}", line);
+            } else {
+                println!("{}", line);
+            }
+        }
+    }
+}
+
+/// 最大`jobs`個のスレッドで各プロジェクトを処理する。出力はプロジェクト毎にまとめて表示し、結果は名前順で返す
+///
+/// `.ccmgen.toml`・`template_provider`の警告と`--verbose`のトレースは発生時に直接標準エラーに出すため、
+/// 他のプロジェクトの出力と前後することがある（グローバル設定の警告は並列処理の前に表示済み）
+fn init_projects_parallel(
+    subdirs: &[PathBuf],
+    base: &Path,
+    config: &Config,
+    dry_run: bool,
+    name_prefix: Option<&str>,
+    jobs: usize,
+) -> Vec<ProjectInitOutcome> {
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let outcomes: std::sync::Mutex<Vec<(usize, ProjectInitOutcome)>> = std::sync::Mutex::new(Vec::new());
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, subdirs.len().max(1)) {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    let Some(subdir) = subdirs.get(index) else {
+                        break;
+                    };
+                    if let Some(outcome) = init_project(subdir, base, config, dry_run, name_prefix) {
+                        // ロック中に表示して、他のプロジェクトの出力と混ざらないようにする
+                        let mut outcomes = outcomes.lock().unwrap_or_else(|e| e.into_inner());
+                        outcome.print_log();
+                        outcomes.push((index, outcome));
+                    }
+                }
+            });
+        }
+    });
+
+    let mut outcomes = outcomes.into_inner().unwrap_or_else(|e| e.into_inner());
+    outcomes.sort_by_key(|(index, _)| *index);
+    outcomes.into_iter().map(|(_, outcome)| outcome).collect()
+}
+
+/// `subdir`のプロジェクトに`.claude/commands`を生成する。プロジェクトでなければNone
+fn init_project(
+    subdir: &Path,
+    base: &Path,
+    config: &Config,
+    dry_run: bool,
+    name_prefix: Option<&str>,
+) -> Option<ProjectInitOutcome> {
+    let context = ProjectDetector::create_project_context(subdir)?;
+    let mut outcome = ProjectInitOutcome {
+        result: RecursiveResult {
+            name: root_display_name(subdir, base),
+            project_type: format!("{:?}", context.info.project_type),
+            created: 0,
+            updated: 0,
+            unchanged: 0,
+            failed: 0,
+        },
+        journal: Journal::default(),
+        log: Vec::new(),
+    };
+    let templates: Vec<(String, String)> = match SmartTemplateManager::create_enhanced_init_templates(&context) {
+        Ok(templates) => templates.into_iter().filter(|(name, _)| !ConfigManager::is_template_disabled(config, name)).collect(),
+        Err(e) => {
+            outcome.log.push((true, format!("❌ {}", e)));
+            outcome.result.failed += 1;
+            return Some(outcome);
+        }
+    };
+    let templates = apply_default_subset(templates, config, context.info.project_type.language_key());
+    let templates = match name_prefix {
+        Some(prefix) => {
+            let lang = context.info.project_type.language_key();
+            apply_name_prefix(templates, &expand_name_prefix(prefix, Some(lang), &context.info.name))
+        }
+        None => templates,
+    };
+    let cmd_dir = subdir.join(".claude").join("commands");
+
+    if dry_run {
+        outcome.log.push((
+            false,
+            format!("📝 {} に {} 個のコマンドを生成します（dry-run）", cmd_dir.display(), templates.len()),
+        ));
+        for (name, _) in &templates {
+            if cmd_dir.join(command_file_name(name)).exists() {
+                outcome.result.updated += 1;
+            } else {
+                outcome.result.created += 1;
+            }
+        }
+        return Some(outcome);
+    }
+
+    if let Err(e) = fs::create_dir_all(&cmd_dir) {
+        outcome.log.push((true, format!("❌ {} の作成に失敗しました: {}", cmd_dir.display(), e)));
+        outcome.result.failed = templates.len();
+        return Some(outcome);
+    }
+    for (name, body) in &templates {
+        let path = cmd_dir.join(command_file_name(name));
+        match save_command(&cmd_dir, name, body) {
+            Ok(SaveOutcome::Created) => {
+                outcome.journal.record_created(path);
+                outcome.result.created += 1;
+            }
+            Ok(SaveOutcome::Updated { previous_content, backup }) => {
+                if let Some(backup) = &backup {
+                    outcome.log.push((false, format!("💾 {} を {} に退避しました", path.display(), backup.display())));
+                }
+                outcome.journal.record_overwritten(path, previous_content, backup);
+                outcome.result.updated += 1;
+            }
+            Ok(SaveOutcome::Unchanged) => outcome.result.unchanged += 1,
+            Err(e) => {
+                outcome.log.push((true, format!("❌ {} の作成に失敗しました: {}", path.display(), e)));
+                outcome.result.failed += 1;
+            }
+        }
+    }
+    outcome.log.push((false, format!("✅ {} にコマンドを生成しました", cmd_dir.display())));
+    Some(outcome)
+}
+
+fn print_recursive_summary(results: &[RecursiveResult], dry_run: bool) {
+    let name_width = results.iter().map(|r| display_width(&r.name)).max().unwrap_or(0).max(12);
+    let (created, updated) = if dry_run { ("作成予定", "上書き予定") } else { ("作成", "更新") };
+
+    println!("\n📊 処理結果{}:", if dry_run { "（dry-run）" } else { "" });
+    let header = [created, updated, "変更なし", "失敗"].map(|h| pad_start(h, 10)).join("");
+    println!("  {}  {}{}", pad_end("プロジェクト", name_width), pad_end("種別", 12), header);
+    for r in results {
+        let counts = [r.created, r.updated, r.unchanged, r.failed].map(|n| pad_start(&n.to_string(), 10)).join("");
+        println!("  {}  {}{}", pad_end(&r.name, name_width), pad_end(&r.project_type, 12), counts);
+    }
+    println!(
+        "🎉 {} 個のプロジェクトを処理しました（{} {}個）",
+        results.len(),
+        created,
+        results.iter().map(|r| r.created).sum::<usize>()
+    );
+}
+
+/// 端末上の表示幅（ASCII以外は全角として2桁で数える）
+fn display_width(text: &str) -> usize {
+    text.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum()
+}
+
+fn pad_end(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(display_width(text))))
+}
+
+fn pad_start(text: &str, width: usize) -> String {
+    format!("{}{}", " ".repeat(width.saturating_sub(display_width(text))), text)
+}
+
+/// 既存のコマンドに空行を挟んでテキストを追記（無ければ作成）。書き込めなかった場合はfalseを返す
+fn append_to_command(name: &str, text: &str) -> bool {
+    let Some(_lock) = acquire_lock() else {
+        return false;
+    };
+
+    let Some(cmd_dir) = resolve_command_dir() else {
+        return false;
+    };
+    if let Err(e) = fs::create_dir_all(&cmd_dir) {
+        eprintln!("❌ コマンドディレクトリの作成に失敗しました: {}", e);
+        return false;
+    }
+
+    let path = cmd_dir.join(command_file_name(name));
+    let existing = match read_existing_command(&path) {
+        Ok(existing) => existing,
+        Err(e) => {
+            eprintln!("❌ {} を読み込めないため追記しません: {}", path.display(), e);
+            return false;
+        }
+    };
+    let body = match &existing {
+        Some(content) if !content.trim().is_empty() => format!("{}\n\n{}", content.trim_end(), text.trim()),
+        _ => text.trim().to_string(),
+    };
+
+    let mut journal = Journal::default();
+    let saved = match save_command(&cmd_dir, name, &body) {
+        Ok(SaveOutcome::Created) => {
+            journal.record_created(path);
+            println!("✅ {} を作成しました", command_file_name(name));
+            true
+        }
+        Ok(SaveOutcome::Updated { previous_content, backup }) => {
+            if let Some(backup) = &backup {
+                println!("💾 {} を {} に退避しました", command_file_name(name), backup.display());
+            }
+            journal.record_overwritten(path, previous_content, backup);
+            println!("➕ {} に追記しました", command_file_name(name));
+            true
+        }
+        Ok(SaveOutcome::Unchanged) => {
+            println!("⏸️ {} は変更ありません (unchanged)", command_file_name(name));
+            true
+        }
+        Err(e) => {
+            eprintln!("❌ {} への追記に失敗しました: {}", command_file_name(name), e);
+            false
+        }
+    };
+
+    if let Err(e) = journal.save_or_clear() {
+        eprintln!("⚠️ 取り消し用ジャーナルの保存に失敗しました: {}", e);
+    }
+    saved
+}
+
+/// 先頭から`max`個だけ残し、省略したテンプレートを案内する
+fn cap_templates(mut templates: Vec<(String, String)>, max: usize) -> Vec<(String, String)> {
+    if templates.len() <= max {
+        return templates;
+    }
+
+    let omitted: Vec<String> = templates.split_off(max).into_iter().map(|(name, _)| name).collect();
+    println!("✂️ --max-templates {} により {} 個のテンプレートを省略しました: {}", max, omitted.len(), omitted.join(", "));
+    println!("   残りを生成するには --template <名前> で個別に生成するか、--max-templates を増やして再実行してください");
+    templates
+}
+
+/// 設定ファイルの`[init] templates`を組み込み・カスタムテンプレートから順番通りに解決
+fn resolve_configured_templates() -> Result<Vec<(String, String)>, InitStop> {
+    let config = ConfigManager::load_config();
+    if config.init.templates.is_empty() {
+        match ConfigManager::get_config_path() {
+            Ok(path) => eprintln!("❌ 設定ファイルに [init] templates が定義されていません: {}", path.display()),
+            Err(_) => eprintln!("❌ 設定ファイルに [init] templates が定義されていません"),
+        }
+        return Err(InitStop::Failed);
+    }
+
+    let mut available: Vec<(String, String)> = TemplateManager::all_builtin_templates()
+        .into_iter()
+        .map(|(name, content)| (name.to_string(), content.to_string()))
+        .collect();
+    for (_, legacy) in get_language_templates() {
+        for (name, content) in legacy {
+            if !available.iter().any(|(n, _)| n == name) {
+                available.push((name.to_string(), content.to_string()));
+            }
+        }
+    }
+    // 衝突は`[init] templates`に挙げたものだけ報告する
+    let requested = |name: &String| config.init.templates.contains(name);
+    let mut merged = TemplateSet::new(config.conflict_policy);
+    merged.add(TemplateSource::Builtin, available.into_iter().filter(|(name, _)| requested(name)));
+    for language in ConfigManager::custom_template_languages() {
+        let custom_templates = SmartTemplateManager::render_custom_templates(&language)
+            .into_iter()
+            .filter(|(name, _, _)| requested(name))
+            .map(|(name, _, content)| (name, content));
+        merged.add(TemplateSource::Custom, custom_templates);
+    }
+    let available = match merged.into_templates() {
+        Ok(available) => available,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return Err(InitStop::Failed);
+        }
+    };
+
+    let mut templates = Vec::new();
+    let mut missing = Vec::new();
+    for name in &config.init.templates {
+        match available.iter().find(|(n, _)| n == name) {
+            Some(found) => templates.push(found.clone()),
+            None => missing.push(name.as_str()),
+        }
+    }
+
+    if !missing.is_empty() {
+        eprintln!("❌ テンプレートが見つかりません: {}", missing.join(", "));
+        return Err(InitStop::Failed);
+    }
+
+    println!("📋 設定ファイルの [init] templates から {} 個のテンプレートを生成します", templates.len());
+    Ok(templates)
+}
+
+/// テンプレート毎に採用・スキップ・編集を対話的に選択
+fn review_templates(templates: Vec<(String, String)>) -> Vec<(String, String)> {
+    let actions = ["採用", "スキップ", "エディタで編集"];
+    let mut reviewed = Vec::new();
+
+    for (name, body) in templates {
+        println!("\n📝 {}:\n{}\n", command_file_name(&name), body);
+        let selection = select(&format!("{} をどうしますか？", name), &actions, 0);
+
+        match selection {
+            0 => reviewed.push((name, body)),
+            1 => println!("⏭️ {} をスキップしました", command_file_name(&name)),
+            _ => match Editor::new().extension(&editor_extension()).edit(&body) {
+                Ok(Some(edited)) => reviewed.push((name, edited)),
+                Ok(None) => {
+                    println!("⚠️ 編集内容が保存されなかったため元の内容を使用します: {}", command_file_name(&name));
+                    reviewed.push((name, body));
+                }
+                Err(e) => {
+                    eprintln!("❌ エディタの起動に失敗しました: {}", e);
+                    reviewed.push((name, body));
+                }
+            },
+        }
+    }
+
+    reviewed
+}
+
+/// この件数を超える一覧はTTYではページャで表示する
+const PAGER_THRESHOLD: usize = 50;
+
+/// `list`・`remove`の出力形式
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// 絵文字付きの表示（デフォルト）
+    Human,
+    /// コマンド名を1行ずつ
+    Plain,
+    /// 構造化されたJSON
+    Json,
+    /// コマンド名をNULバイト区切りで（`xargs -0`向け）
+    Null,
+}
+
+/// コマンド名を`plain`（改行区切り）または`null`（NUL区切り）で出力
+fn print_names<'a>(names: impl IntoIterator<Item = &'a str>, format: OutputFormat) {
+    let separator = if format == OutputFormat::Null { '\0' } else { '\n' };
+    let mut stdout = io::stdout().lock();
+    for name in names {
+        let _ = write!(stdout, "{}{}", name, separator);
+    }
+}
+
+/// `ccmgen remove --output-format json` の1件分
+#[derive(Serialize)]
+struct RemoveResult {
+    name: String,
+    path: PathBuf,
+    /// `--dry-run`では常にfalse
+    removed: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    dry_run: bool,
+    /// `--dry-run`で削除対象であればtrue
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    would_remove: bool,
+    /// 削除できなかった理由
+    error: Option<String>,
+}
+
+fn print_remove_results(results: &[RemoveResult], format: OutputFormat) {
+    match format {
+        OutputFormat::Human => {}
+        OutputFormat::Json => match serde_json::to_string_pretty(results) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("❌ JSONへの変換に失敗しました: {}", e),
+        },
+        OutputFormat::Plain | OutputFormat::Null => {
+            print_names(results.iter().filter(|r| r.removed || r.would_remove).map(|r| r.name.as_str()), format);
+        }
+    }
+}
+
+/// `ccmgen list --json` の1件分
+#[derive(Serialize)]
+struct CommandEntry {
+    name: String,
+    path: PathBuf,
+    /// バイト数
+    size: u64,
+    /// 最終更新日時（ISO 8601, UTC）
+    modified: Option<String>,
+    first_line: Option<String>,
+    /// フロントマターの`author`・`version`
+    #[serde(flatten)]
+    metadata: TemplateMetadata,
+}
+
+/// コマンドディレクトリ内のコマンドファイル（`--commands-ext`の拡張子、名前順）
+fn command_files() -> Vec<PathBuf> {
+    let Ok(dir) = get_command_dir() else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_file()
+                        && match commands_ext() {
+                            "" => path.extension().is_none(),
+                            ext => path.extension().is_some_and(|e| e == ext),
+                        }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+/// コマンド一覧をJSON配列で出力
+fn list_json(prefix: Option<&str>) {
+    let entries: Vec<CommandEntry> = command_files()
+        .into_iter()
+        .filter(|path| {
+            prefix.is_none_or(|p| path.file_name().is_some_and(|n| n.to_string_lossy().starts_with(p)))
+        })
+        .filter_map(|path| {
+            let metadata = fs::metadata(&path).ok()?;
+            let content = fs::read_to_string(&path).ok();
+            let first_line = content.as_deref().and_then(|content| {
+                parse_frontmatter(content)
+                    .1
+                    .lines()
+                    .map(str::trim)
+                    .find(|line| !line.is_empty())
+                    .map(|line| line.to_string())
+            });
+            Some(CommandEntry {
+                name: path.file_stem()?.to_string_lossy().to_string(),
+                size: metadata.len(),
+                modified: metadata
+                    .modified()
+                    .ok()
+                    .map(|t| humantime::format_rfc3339_seconds(t).to_string()),
+                first_line,
+                metadata: content.as_deref().map(TemplateMetadata::from_content).unwrap_or_default(),
+                path,
+            })
+        })
+        .collect();
+
+    match serde_json::to_string_pretty(&entries) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("❌ JSONへの変換に失敗しました: {}", e),
+    }
+}
+
+/// `claude-cli list` コマンド
+///
+/// `prefix`を指定するとそのプレフィックスで始まるコマンドのみを表示する
+///
+/// `verbose`なら各コマンドのフロントマターの作成者・バージョンも表示する
+pub fn list(
+    page: Option<usize>,
+    per_page: usize,
+    no_pager: bool,
+    format: OutputFormat,
+    prefix: Option<&str>,
+    verbose: bool,
+    tree: bool,
+) {
+    match format {
+        OutputFormat::Human => {}
+        OutputFormat::Json => {
+            list_json(prefix);
+            return;
+        }
+        OutputFormat::Plain | OutputFormat::Null => {
+            let names: Vec<String> = command_files()
+                .iter()
+                .filter_map(|path| path.file_stem().map(|n| n.to_string_lossy().to_string()))
+                .filter(|name| prefix.is_none_or(|p| name.starts_with(p)))
+                .collect();
+            print_names(names.iter().map(String::as_str), format);
+            return;
+        }
+    }
+
+    let Some(dir) = resolve_command_dir() else {
+        return;
+    };
+    if !dir.exists() {
+        println!("{}", t!("list.empty"));
+        return;
+    }
+
+    let names: Vec<String> = command_files()
+        .iter()
+        .filter_map(|path| path.file_name().map(|n| n.to_string_lossy().to_string()))
+        .filter(|name| prefix.is_none_or(|p| name.starts_with(p)))
+        .collect();
+
+    let total = names.len();
+    let mut output = format!("{}\n", t!("list.title"));
+    if let Some(page) = page {
+        let per_page = per_page.max(1);
+        let pages = total.div_ceil(per_page).max(1);
+        let page = page.clamp(1, pages);
+        for name in names.iter().skip((page - 1) * per_page).take(per_page) {
+            output.push_str(&list_line(&dir, name, verbose));
+        }
+        output.push_str(&format!("📄 {}/{} ページ（全{}件）\n", page, pages, total));
+    } else if tree {
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        push_tree(&mut output, &dir, &names, 0, " ", verbose);
+    } else {
+        for name in &names {
+            output.push_str(&list_line(&dir, name, verbose));
+        }
+    }
+
+    let use_pager = page.is_none() && !no_pager && total > PAGER_THRESHOLD && io::stdout().is_terminal();
+    if !use_pager || !show_in_pager(&output) {
+        print!("{}", output);
+    }
+}
+
+/// `list`の1行。`verbose`ならフロントマターの作成者・バージョンを添える
+fn list_line(dir: &Path, name: &str, verbose: bool) -> String {
+    format!(" - {}\n", list_entry(dir, name, verbose))
+}
+
+fn list_entry(dir: &Path, name: &str, verbose: bool) -> String {
+    let metadata = if verbose {
+        fs::read_to_string(dir.join(name)).map(|c| TemplateMetadata::from_content(&c)).unwrap_or_default()
+    } else {
+        TemplateMetadata::default()
+    };
+    match format_metadata(&metadata) {
+        Some(meta) => format!("{}  ({})", name, meta),
+        None => name.to_string(),
+    }
+}
+
+/// `list --tree`: ファイル名（拡張子を除く）を`-`で区切り、共通のプレフィックス毎に入れ子にして表示する
+///
+/// 2件以上が共有する区切りをまとめ、1件しかない区切りはそのまま葉として表示する。`names`は名前順であること
+fn push_tree(output: &mut String, dir: &Path, names: &[&str], depth: usize, indent: &str, verbose: bool) {
+    let segments = |name: &str| -> Vec<String> {
+        let stem = name.split('.').next().unwrap_or(name);
+        stem.split('-').map(str::to_string).collect()
+    };
+
+    // (この階層の区切り, 含まれる名前)。区切りが尽きた名前は単独の葉にする
+    let mut groups: Vec<(Option<String>, Vec<&str>)> = Vec::new();
+    for name in names {
+        let key = segments(name).get(depth).cloned();
+        match groups.iter_mut().find(|(k, _)| key.is_some() && *k == key) {
+            Some((_, members)) => members.push(name),
+            None => groups.push((key, vec![name])),
+        }
+    }
+
+    for (i, (_, members)) in groups.iter().enumerate() {
+        let last = i + 1 == groups.len();
+        let (branch, child_indent) = if last { ("└── ", "    ") } else { ("├── ", "│   ") };
+        if members.len() == 1 {
+            output.push_str(&format!("{}{}{}\n", indent, branch, list_entry(dir, members[0], verbose)));
+            continue;
+        }
+
+        // 全員が次の区切りも共有していれば1つの枝にまとめる（`review-sql-`など）。
+        // 区切りが尽きる名前（`review-sql`）も含めてまとめ、子が1つだけの枝が続かないようにする
+        let member_segments: Vec<Vec<String>> = members.iter().map(|m| segments(m)).collect();
+        let mut end = depth + 1;
+        while member_segments.iter().all(|s| s.len() > end && s[end] == member_segments[0][end]) {
+            end += 1;
+        }
+        output.push_str(&format!("{}{}{}-\n", indent, branch, member_segments[0][..end].join("-")));
+        push_tree(output, dir, members, end, &format!("{}{}", indent, child_indent), verbose);
+    }
+}
+
+/// `作成者: X, バージョン: Y`の形式。どちらも無ければNone
+fn format_metadata(metadata: &TemplateMetadata) -> Option<String> {
+    let parts: Vec<String> = [("作成者", &metadata.author), ("バージョン", &metadata.version)]
+        .into_iter()
+        .filter_map(|(label, value)| value.as_ref().map(|v| format!("{}: {}", label, v)))
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
+/// `$PAGER`（既定は`less`）に出力を渡す。起動できなければfalseを返す
+fn show_in_pager(output: &str) -> bool {
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| "less".to_string());
+    let mut parts = pager.split_whitespace();
+    let Some(program) = parts.next() else {
+        return false;
+    };
+
+    let Ok(mut child) = Command::new(program).args(parts).stdin(Stdio::piped()).spawn() else {
+        return false;
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // ページャが途中で終了した場合の書き込みエラーは無視する
+        let _ = stdin.write_all(output.as_bytes());
+    }
+    let _ = child.wait();
+    true
+}
+
+/// `remove`の結果（終了コードの判定用）
+#[derive(PartialEq)]
+pub enum RemoveStatus {
+    Done,
+    /// 削除するコマンドが無い（`--strict`のときだけ終了コード1）
+    NotFound,
+    /// 削除やロックの取得に失敗した（表示済み、常に終了コード1）
+    Failed,
+}
+
+/// `ccmgen remove <name>` コマンド
+///
+/// `dry_run`なら削除対象のパスを表示するだけで削除しない
+pub fn remove(name: &str, dry_run: bool, format: OutputFormat) -> RemoveStatus {
+    let _lock = if dry_run {
+        None
+    } else {
+        let Some(lock) = acquire_lock() else {
+            return RemoveStatus::Failed;
+        };
+        Some(lock)
+    };
+
+    let Some(dir) = resolve_command_dir() else {
+        return RemoveStatus::Failed;
+    };
+    let name = resolve_command_name(name);
+    let path = dir.join(command_file_name(&name));
+    let result = if path.exists() {
+        if dry_run {
+            if format == OutputFormat::Human {
+                println!("{}", t!("remove.dry_run", path.display()));
+            }
+            RemoveResult { name, path, removed: false, dry_run, would_remove: true, error: None }
+        } else {
+            match fs::remove_file(&path).map_err(CcmgenError::Io) {
+                Ok(()) => {
+                    if format == OutputFormat::Human {
+                        println!("{}", t!("remove.done", command_file_name(&name)));
+                    }
+                    RemoveResult { name, path, removed: true, dry_run, would_remove: false, error: None }
+                }
+                Err(e) => {
+                    eprintln!("❌ {} の削除に失敗しました: {}", path.display(), e);
+                    RemoveResult { name, path, removed: false, dry_run, would_remove: false, error: Some(e.to_string()) }
+                }
+            }
+        }
+    } else {
+        match format {
+            OutputFormat::Human => println!("{}", t!("remove.not_found", command_file_name(&name))),
+            _ => eprintln!("{}", t!("remove.not_found", command_file_name(&name))),
+        }
+        RemoveResult { name, path, removed: false, dry_run, would_remove: false, error: Some("not found".to_string()) }
+    };
+    let status = match &result.error {
+        None => RemoveStatus::Done,
+        Some(_) if !result.path.exists() => RemoveStatus::NotFound,
+        Some(_) => RemoveStatus::Failed,
+    };
+    print_remove_results(&[result], format);
+    status
 }
 
-/// ユーザーディレクトリのパス取得
-fn get_command_dir() -> PathBuf {
-    home_dir()
-        .expect("Could not get home directory")
-        .join(".claude/commands")
-}
+/// コマンドディレクトリ内のコマンドファイルをすべて削除（拡張子の異なるファイルは残す）
+///
+/// `dry_run`なら確認せずに削除対象を一覧表示する
+pub fn remove_all(force: bool, dry_run: bool, format: OutputFormat) -> RemoveStatus {
+    let Some(dir) = resolve_command_dir() else {
+        return RemoveStatus::Failed;
+    };
+    let files = command_files();
 
-/// `ccmgen detect` コマンド本体
-pub fn detect(path: Option<String>) {
-    let target_path = path
-        .map(PathBuf::from)
-        .unwrap_or_else(|| std::env::current_dir().expect("カレントディレクトリの取得に失敗しました"));
+    if files.is_empty() {
+        match format {
+            OutputFormat::Human => println!("❓ 削除するコマンドがありません: {}", dir.display()),
+            _ => eprintln!("❓ 削除するコマンドがありません: {}", dir.display()),
+        }
+        print_remove_results(&[], format);
+        return RemoveStatus::NotFound;
+    }
 
-    match ProjectDetector::detect_project(&target_path) {
-        Some(project) => {
-            println!("🔍 プロジェクト検出結果:");
-            println!("  名前: {}", project.name);
-            println!("  種別: {:?}", project.project_type);
-            println!("  パス: {}", project.path.display());
-            if !project.features.is_empty() {
-                println!("  機能: {}", project.features.join(", "));
-            }
+    if dry_run {
+        let results: Vec<RemoveResult> = files
+            .into_iter()
+            .map(|path| {
+                if format == OutputFormat::Human {
+                    println!("{}", t!("remove.dry_run", path.display()));
+                }
+                let name = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+                RemoveResult { name, path, removed: false, dry_run, would_remove: true, error: None }
+            })
+            .collect();
+        if format == OutputFormat::Human {
+            println!("🔍 {} 個のコマンドが削除対象です（--dry-run を外すと削除します）", results.len());
         }
-        None => {
-            println!("❓ 対応するプロジェクトタイプが見つかりませんでした");
+        print_remove_results(&results, format);
+        return RemoveStatus::Done;
+    }
+
+    if !force {
+        let confirmed = confirm(
+            &format!(
+                "⚠️ {} 内の {} 個のコマンドをすべて削除します。この操作は取り消せません。続行しますか？",
+                dir.display(),
+                files.len()
+            ),
+            false,
+        );
+        if !confirmed {
+            if !prompts_enabled() {
+                eprintln!("💡 対話できないため削除しません。確認せずに削除するには --force");
+            }
+            match format {
+                OutputFormat::Human => println!("🚫 中止しました"),
+                _ => eprintln!("🚫 中止しました"),
+            }
+            return RemoveStatus::Done;
         }
     }
+
+    let Some(_lock) = acquire_lock() else {
+        return RemoveStatus::Failed;
+    };
+
+    let results: Vec<RemoveResult> = files
+        .into_iter()
+        .map(|path| {
+            let name = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            match fs::remove_file(&path) {
+                Ok(_) => RemoveResult { name, path, removed: true, dry_run, would_remove: false, error: None },
+                Err(e) => {
+                    eprintln!("❌ {} の削除に失敗しました: {}", path.display(), e);
+                    RemoveResult { name, path, removed: false, dry_run, would_remove: false, error: Some(e.to_string()) }
+                }
+            }
+        })
+        .collect();
+
+    if format == OutputFormat::Human {
+        println!("🗑️ {} 個のコマンドを削除しました", results.iter().filter(|r| r.removed).count());
+    }
+    let failed = results.iter().any(|r| r.error.is_some());
+    print_remove_results(&results, format);
+    if failed { RemoveStatus::Failed } else { RemoveStatus::Done }
 }
 
-/// `ccmgen init` コマンド本体
-pub fn init(lang: Option<String>, repo: Option<String>, path: Option<String>) {
-    if let Some(repo_url) = repo {
-        println!("🔗 GitHubテンプレートのダウンロードは未実装です: {repo_url}");
-        // TODO: GitHub連携処理（git2またはreqwest+zip）
+/// `ccmgen sync`: コマンドをgitリポジトリの`.claude/commands`にコピーしてコミット・プッシュ
+pub fn sync(repo: &str, message: Option<String>) {
+    let Some(cmd_dir) = resolve_command_dir() else {
+        return;
+    };
+    let files = command_files();
+
+    if files.is_empty() {
+        println!("❓ 同期するコマンドがありません: {}", cmd_dir.display());
         return;
     }
 
-    let target_path = path
-        .map(PathBuf::from)
-        .unwrap_or_else(|| std::env::current_dir().expect("カレントディレクトリの取得に失敗しました"));
+    let work_dir = std::env::temp_dir().join(format!("ccmgen-sync-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&work_dir);
 
-    // プロジェクト自動検出を試行
-    let project_context = if lang.is_none() {
-        ProjectDetector::create_project_context(&target_path)
-    } else {
-        None
+    // クローンは一時ディレクトリで実行するため、ローカルのリポジトリは絶対パスにしておく
+    let repo = match Path::new(repo).canonicalize() {
+        Ok(path) => path.to_string_lossy().to_string(),
+        Err(_) => repo.to_string(),
     };
+    println!("📥 クローンしています: {}", repo);
+    if let Err(e) = git::clone(&repo, &work_dir) {
+        eprintln!("❌ リポジトリのクローンに失敗しました: {}", e);
+        return;
+    }
 
-    let templates = if let Some(ref context) = project_context {
-        println!("🔍 プロジェクトを検出しました: {} ({:?})", context.info.name, context.info.project_type);
-        println!("💡 {} 個のプロジェクト固有コマンドを含みます", context.suggested_commands.len());
-        SmartTemplateManager::create_enhanced_init_templates(context)
-    } else {
-        // 手動選択または古いロジック
-        let legacy_templates = get_language_templates();
-        let selected_lang = match lang {
-            Some(lang) => lang,
-            None => {
-                let langs: Vec<&str> = legacy_templates.iter().map(|(l, _)| *l).collect();
-                let selection = Select::with_theme(&ColorfulTheme::default())
-                    .with_prompt("言語を選択してください")
-                    .items(&langs)
-                    .default(0)
-                    .interact()
-                    .unwrap();
-                langs[selection].to_string()
-            }
-        };
+    sync_into_clone(&work_dir, &files, message);
 
-        legacy_templates
-            .iter()
-            .find(|(l, _)| *l == selected_lang)
-            .map(|(_, t)| t.iter().map(|(name, content)| (name.to_string(), content.to_string())).collect())
-            .expect("テンプレートが見つかりません")
-    };
+    if let Err(e) = fs::remove_dir_all(&work_dir) {
+        eprintln!("⚠️ 作業ディレクトリの削除に失敗しました: {}: {}", work_dir.display(), e);
+    }
+}
+
+fn sync_into_clone(work_dir: &Path, files: &[PathBuf], message: Option<String>) {
+    let dest_dir = work_dir.join(".claude").join("commands");
+    if let Err(e) = fs::create_dir_all(&dest_dir) {
+        eprintln!("❌ {} の作成に失敗しました: {}", dest_dir.display(), e);
+        return;
+    }
+    for file in files {
+        let Some(file_name) = file.file_name() else {
+            continue;
+        };
+        if let Err(e) = fs::copy(file, dest_dir.join(file_name)) {
+            eprintln!("❌ {} のコピーに失敗しました: {}", file.display(), e);
+            return;
+        }
+    }
 
-    let cmd_dir = get_command_dir();
-    fs::create_dir_all(&cmd_dir).expect("コマンドディレクトリの作成に失敗しました");
+    let message = message.unwrap_or_else(|| format!("Sync {} Claude commands", files.len()));
+    match git::commit_all(work_dir, &message) {
+        Ok(true) => println!("📝 コミットしました: {}", message),
+        Ok(false) => {
+            println!("⏸️ リポジトリのコマンドは最新です（変更なし）");
+            return;
+        }
+        Err(e) => {
+            eprintln!("❌ コミットに失敗しました: {}", e);
+            return;
+        }
+    }
 
-    for (name, body) in templates {
-        match save_command(&name, &body) {
-            Ok(_) => println!("✅ {}.md を作成しました", name),
-            Err(e) => eprintln!("❌ {}.md の作成に失敗しました: {}", name, e),
+    if let Err(e) = git::push(work_dir, false) {
+        // 認証・通信のエラーでは強制プッシュしても解決しないため、履歴の食い違いで拒否された場合だけ提案する
+        if !git::is_non_fast_forward(&e) {
+            eprintln!("❌ プッシュに失敗しました: {}", e);
+            return;
+        }
+        eprintln!("⚠️ リモートに取り込まれていない変更があるためプッシュが拒否されました: {}", e);
+        let force = confirm("リモートの履歴を上書きして強制プッシュしますか？", false);
+        if !force {
+            println!("🚫 プッシュを中止しました");
+            return;
+        }
+        if let Err(e) = git::push(work_dir, true) {
+            eprintln!("❌ 強制プッシュに失敗しました: {}", e);
+            return;
         }
     }
 
-    println!("🎉 完了しました: ~/.claude/commands にコマンドが作成されました");
+    println!("🎉 {} 個のコマンドを同期しました", files.len());
 }
 
-/// `claude-cli list` コマンド
-pub fn list() {
-    let dir = get_command_dir();
-    if !dir.exists() {
-        println!("⚠️ ユーザーコマンドはまだ存在しません");
+/// `ccmgen undo` コマンド
+pub fn undo() {
+    let Some(journal) = Journal::load() else {
+        println!("❓ 取り消し可能な操作がありません");
+        return;
+    };
+    let Some(_lock) = acquire_lock() else {
         return;
+    };
+
+    for path in &journal.created {
+        match fs::remove_file(path) {
+            Ok(_) => println!("🗑️ 削除しました: {}", path.display()),
+            Err(e) => eprintln!("❌ {} の削除に失敗しました: {}", path.display(), e),
+        }
+    }
+    for file in &journal.overwritten {
+        match write_atomic(&file.path, &file.previous_content) {
+            Ok(_) => println!("↩️ 復元しました: {}", file.path.display()),
+            Err(e) => eprintln!("❌ {} の復元に失敗しました: {}", file.path.display(), e),
+        }
+    }
+    // 退避したファイルは取り消し後も残す（不要なら手動で削除）
+    let backups: Vec<&PathBuf> = journal.overwritten.iter().filter_map(|f| f.backup.as_ref()).filter(|b| b.exists()).collect();
+    if !backups.is_empty() {
+        println!("💾 --backup-existing で退避したファイルは残しています:");
+        for backup in backups {
+            println!("  - {}", backup.display());
+        }
     }
 
-    println!("📋 現在のユーザーコマンド一覧:");
-    for entry in fs::read_dir(&dir).unwrap() {
-        if let Ok(file) = entry {
-            if let Some(name) = file.path().file_name() {
-                println!(" - {}", name.to_string_lossy());
-            }
+    match Journal::clear() {
+        Ok(_) => println!("🎉 直近の init を取り消しました"),
+        Err(e) => eprintln!("⚠️ ジャーナルの削除に失敗しました: {}", e),
+    }
+}
+
+/// `ccmgen which <name>` コマンド
+///
+/// コマンドファイルの絶対パスを表示し、存在するかどうかを返す
+pub fn which(name: &str) -> bool {
+    let Some(dir) = resolve_command_dir() else {
+        return false;
+    };
+    let name = resolve_command_name(name);
+    let path = dir.join(command_file_name(&name));
+    println!("{}", path.display());
+    path.exists()
+}
+
+/// `ccmgen show` コマンド。`copy`指定時はクリップボードにコピーし、使えなければ表示する
+pub fn show(name: &str, copy: bool, meta: bool) -> bool {
+    let Some(dir) = resolve_command_dir() else {
+        return false;
+    };
+    let name = resolve_command_name(name);
+    let path = dir.join(command_file_name(&name));
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(_) => {
+            eprintln!("{}", t!("remove.not_found", command_file_name(&name)));
+            return false;
+        }
+    };
+
+    if meta {
+        match format_metadata(&TemplateMetadata::from_content(&content)) {
+            Some(metadata) => println!("📝 {}: {}", command_file_name(&name), metadata),
+            None => println!("📝 {} には作成者・バージョンの情報がありません", command_file_name(&name)),
+        }
+        return true;
+    }
+
+    if copy {
+        match copy_to_clipboard(&content, &command_file_name(&name)) {
+            Ok(()) => return true,
+            Err(e) => eprintln!("⚠️ クリップボードを利用できないため表示します: {}", e),
         }
     }
+
+    print!("{}", content);
+    if !content.ends_with('\n') {
+        println!();
+    }
+    true
 }
 
-/// `ccmgen remove <name>` コマンド
-pub fn remove(name: &str) {
-    let path = get_command_dir().join(format!("{name}.md"));
-    if path.exists() {
-        fs::remove_file(&path).expect("ファイル削除に失敗しました");
-        println!("🗑️ 削除しました: {name}.md");
-    } else {
-        println!("❓ 指定されたコマンドが見つかりません: {name}.md");
+/// `show --copy`の補助プロセスが準備できたときに標準出力へ書く行
+const CLIPBOARD_READY: &str = "ready";
+
+/// クリップボードにテキストを設定する
+///
+/// X11/Waylandではコピー元のプロセスが終了すると内容が消えるため、内容を保持する補助プロセスを切り離して起動する
+fn copy_to_clipboard(content: &str, label: &str) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        use std::io::{BufRead, BufReader};
+        use std::os::unix::process::CommandExt;
+
+        let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+        // 別のプロセスグループにして端末の Ctrl+C が届かないようにする
+        let mut child = Command::new(exe)
+            .arg("__hold-clipboard")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .process_group(0)
+            .spawn()
+            .map_err(|e| e.to_string())?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(content.as_bytes()).map_err(|e| e.to_string())?;
+        }
+
+        let mut status = String::new();
+        if let Some(stdout) = child.stdout.take() {
+            BufReader::new(stdout).read_line(&mut status).map_err(|e| e.to_string())?;
+        }
+        if status.trim() != CLIPBOARD_READY {
+            let _ = child.wait();
+            return Err(match status.trim() {
+                "" => "補助プロセスが終了しました".to_string(),
+                message => message.to_string(),
+            });
+        }
+        eprintln!("📋 {} をクリップボードにコピーしました（他の内容がコピーされるまでバックグラウンドで保持します）", label);
+        Ok(())
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+        clipboard.set_text(content).map_err(|e| e.to_string())?;
+        eprintln!("📋 {} をクリップボードにコピーしました", label);
+        Ok(())
+    }
+}
+
+/// `show --copy`の補助プロセス。標準入力の内容をクリップボードに設定し、他の内容がコピーされるまで保持する
+///
+/// 準備できたら`CLIPBOARD_READY`、失敗したらエラーを標準出力に1行書く
+pub fn hold_clipboard() -> bool {
+    let mut content = String::new();
+    if let Err(e) = io::Read::read_to_string(&mut io::stdin(), &mut content) {
+        println!("{}", e);
+        return false;
+    }
+    let mut clipboard = match arboard::Clipboard::new() {
+        Ok(clipboard) => clipboard,
+        Err(e) => {
+            println!("{}", e);
+            return false;
+        }
+    };
+    println!("{}", CLIPBOARD_READY);
+
+    #[cfg(target_os = "linux")]
+    {
+        use arboard::SetExtLinux;
+        clipboard.set().wait().text(content).is_ok()
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        clipboard.set_text(content).is_ok()
     }
 }
 
 /// `ccmgen config` コマンド
 pub fn config() {
+    let Some(_lock) = acquire_lock() else {
+        return;
+    };
+
     match ConfigManager::create_default_config() {
         Ok(_) => println!("🎉 設定ファイルが作成されました"),
         Err(e) => eprintln!("❌ 設定ファイルの作成に失敗しました: {}", e),
     }
 }
 
-/// `ccmgen analyze` コマンド
-pub fn analyze(path: Option<String>) {
+/// `ccmgen templates --export` コマンド。組み込みテンプレートを`<name>.md`（`--commands-ext`の拡張子）として書き出す
+///
+/// 説明（プロンプトの1行目）はフロントマターに入れるので、そのまま`~/.claude/templates/<language>/`に置いて読み込める
+pub fn export_templates(lang: &str, dir: &Path) -> bool {
+    let templates = templates_for_language(lang);
+    if templates.is_empty() {
+        eprintln!("❓ {} の組み込みテンプレートが見つかりませんでした", lang);
+        return false;
+    }
+    if let Err(e) = fs::create_dir_all(dir) {
+        eprintln!("❌ ディレクトリの作成に失敗しました: {}: {}", dir.display(), e);
+        return false;
+    }
+
+    let mut failed = 0;
+    for (name, content) in &templates {
+        let description = content.lines().next().unwrap_or_default().trim().trim_end_matches(':');
+        let body = format!(
+            "---\ndescription: \"{}\"\n---\n\n{}",
+            description.replace('\\', "\\\\").replace('"', "\\\""),
+            content
+        );
+        match save_command(dir, name, &body) {
+            Ok(SaveOutcome::Created) => println!("✅ {} を書き出しました", command_file_name(name)),
+            Ok(SaveOutcome::Updated { .. }) => println!("🔄 {} を更新しました", command_file_name(name)),
+            Ok(SaveOutcome::Unchanged) => println!("⏸️ {} は変更ありません (unchanged)", command_file_name(name)),
+            Err(e) => {
+                failed += 1;
+                eprintln!("❌ {} の書き出しに失敗しました: {}", command_file_name(name), e);
+            }
+        }
+    }
+
+    println!(
+        "🎉 {} 個のテンプレートを {} に書き出しました",
+        templates.len() - failed,
+        dir.display()
+    );
+    if let Ok(templates_dir) = ConfigManager::get_templates_dir() {
+        println!(
+            "💡 編集後に {} に置くとカスタムテンプレートとして読み込まれます",
+            templates_dir.join(lang).display()
+        );
+    }
+    failed == 0
+}
+
+/// `ccmgen config migrate` コマンド。更新に失敗した場合はfalseを返す
+pub fn config_migrate() -> bool {
+    let config_path = match ConfigManager::get_config_path() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return false;
+        }
+    };
+    if !config_path.exists() {
+        println!("❓ 設定ファイルがありません: {}（ccmgen config で作成できます）", config_path.display());
+        return true;
+    }
+
+    let Some(_lock) = acquire_lock() else {
+        return false;
+    };
+
+    match ConfigManager::migrate_config() {
+        Ok(MigrationOutcome::UpToDate) => {
+            println!("✅ 設定ファイルは最新の形式です (version {})", CONFIG_VERSION);
+            true
+        }
+        Ok(MigrationOutcome::Migrated { from_version, backup_path, dropped_keys }) => {
+            if !dropped_keys.is_empty() {
+                println!("⚠️ 現在の形式に無い項目を削除しました: {}", dropped_keys.join(", "));
+            }
+            println!("💾 元の設定ファイルを退避しました: {}", backup_path.display());
+            println!(
+                "🎉 設定ファイルを更新しました (version {} → {}): {}",
+                from_version,
+                CONFIG_VERSION,
+                config_path.display()
+            );
+            true
+        }
+        Err(e) => {
+            eprintln!("❌ 設定ファイルの更新に失敗しました: {}", e);
+            false
+        }
+    }
+}
+
+/// `ccmgen schema` コマンド
+pub fn schema() {
+    match ConfigManager::config_schema() {
+        Ok(schema) => println!("{}", schema),
+        Err(e) => eprintln!("❌ スキーマの生成に失敗しました: {}", e),
+    }
+}
+
+/// `ccmgen analyze`の出力形式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnalyzeOutput {
+    Human,
+    /// 推奨コマンド名のみ
+    SuggestOnly,
+    Json,
+}
+
+/// `analyze --sort-deps`の並び順
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum DepsSort {
+    /// 名前順（デフォルト）
+    Name,
+    /// 参照しているソース・テストファイル数の多い順
+    Count,
+}
+
+/// `analyze`で表示する依存関係の既定の件数
+pub const DEFAULT_DEPS_LIMIT: usize = 10;
+
+/// `analyze`の依存関係の表示件数（Noneなら全て）と並び順
+static DEPS_VIEW: OnceLock<(Option<usize>, DepsSort)> = OnceLock::new();
+
+/// `analyze`の依存関係の表示件数・並び順を設定する
+pub fn set_deps_view(limit: Option<usize>, sort: DepsSort) {
+    let _ = DEPS_VIEW.set((limit, sort));
+}
+
+/// `ccmgen analyze`。プロジェクトを検出できなかった場合はfalseを返す
+pub fn analyze(
+    path: Option<String>,
+    glob: Option<String>,
+    since: Option<String>,
+    check_docs: bool,
+    output: AnalyzeOutput,
+    watch: bool,
+) -> bool {
+    if let Some(pattern) = glob {
+        return analyze_glob(&pattern, since.as_deref(), check_docs, output);
+    }
     let target_path = path
         .map(PathBuf::from)
         .unwrap_or_else(|| std::env::current_dir().expect("カレントディレクトリの取得に失敗しました"));
 
-    match ProjectDetector::create_project_context(&target_path) {
-        Some(context) => {
+    if watch {
+        watch_analyze(&target_path, since.as_deref(), check_docs);
+        return true;
+    }
+    run_analyze(&target_path, since.as_deref(), check_docs, output).is_some()
+}
+
+/// `analyze --glob`。一致した各ディレクトリを分析してまとめて表示し（JSONは配列）、全て検出できればtrue
+fn analyze_glob(pattern: &str, since: Option<&str>, check_docs: bool, output: AnalyzeOutput) -> bool {
+    let Some(roots) = expand_glob_roots(pattern) else {
+        return false;
+    };
+
+    let mut detected = 0;
+    let mut values = Vec::new();
+    for root in &roots {
+        if output == AnalyzeOutput::Human {
+            println!("\n📂 {}", root.display());
+        }
+        let (context, fullstack) = analyze_project(root, since, check_docs, output);
+        if output == AnalyzeOutput::Json {
+            values.extend(analyze_json(root, context.as_ref(), fullstack.as_ref()));
+        } else if let Some(layout) = &fullstack {
+            print_fullstack_areas(layout, output == AnalyzeOutput::SuggestOnly);
+        }
+        if context.is_some() || fullstack.is_some() {
+            detected += 1;
+        }
+    }
+
+    match output {
+        AnalyzeOutput::Json => print_json(&serde_json::Value::Array(values)),
+        AnalyzeOutput::Human => {
+            println!("\n📊 {} に一致する {} 個のディレクトリ中 {} 個のプロジェクトを分析しました", pattern, roots.len(), detected);
+        }
+        AnalyzeOutput::SuggestOnly => {}
+    }
+    detected == roots.len()
+}
+
+/// 変更を検知してから再分析するまでの待ち時間（この間の連続したイベントはまとめる）
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// ディレクトリを監視し、関連ファイルの変更毎に画面をクリアして分析結果を再表示
+fn watch_analyze(target_path: &Path, since: Option<&str>, check_docs: bool) {
+    let root = fs::canonicalize(target_path).unwrap_or_else(|_| target_path.to_path_buf());
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(tx) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            eprintln!("❌ ファイル監視を開始できませんでした: {}", e);
+            return;
+        }
+    };
+
+    let mut context = run_watch_cycle(&root, since, check_docs);
+    let mut watched = HashSet::new();
+    if let Err(e) = sync_watches(&mut watcher, &root, context.as_ref(), &mut watched) {
+        eprintln!("❌ ファイル監視を開始できませんでした: {}", e);
+        return;
+    }
+    // 受信側が閉じられるまで（Ctrl-Cで終了）
+    while let Ok(event) = rx.recv() {
+        if !is_relevant_event(&event, &root, context.as_ref()) {
+            continue;
+        }
+        // 保存・ビルドによる連続したイベントが落ち着くまで待つ
+        while rx.recv_timeout(WATCH_DEBOUNCE).is_ok() {}
+        context = run_watch_cycle(&root, since, check_docs);
+        // 追加・削除されたディレクトリを監視対象に反映
+        if let Err(e) = sync_watches(&mut watcher, &root, context.as_ref(), &mut watched) {
+            eprintln!("⚠️ ファイル監視を更新できませんでした: {}", e);
+        }
+    }
+}
+
+/// 生成物・依存ディレクトリや`ignore_dirs`を除いたディレクトリを個別に（再帰せずに）監視する
+///
+/// `target`・`node_modules`などを再帰的に監視するとinotifyの上限に達しやすいため。
+/// ルートを監視できなければエラー、それ以外のディレクトリの失敗は無視する
+fn sync_watches(
+    watcher: &mut impl notify::Watcher,
+    root: &Path,
+    context: Option<&ProjectContext>,
+    watched: &mut HashSet<PathBuf>,
+) -> notify::Result<()> {
+    let directories: HashSet<PathBuf> = match context {
+        Some(context) => context.structure.watch_directories(root),
+        None => ProjectStructure::new().watch_directories(root),
+    }
+    .into_iter()
+    .collect();
+
+    for dir in watched.difference(&directories) {
+        let _ = watcher.unwatch(dir);
+    }
+    let mut result = Ok(());
+    let mut now_watched = HashSet::new();
+    for dir in directories {
+        if watched.contains(&dir) {
+            now_watched.insert(dir);
+            continue;
+        }
+        match watcher.watch(&dir, notify::RecursiveMode::NonRecursive) {
+            Ok(()) => {
+                now_watched.insert(dir);
+            }
+            Err(e) if dir == root => result = Err(e),
+            Err(_) => {}
+        }
+    }
+    *watched = now_watched;
+    result
+}
+
+/// 画面をクリアして分析結果を表示し、次の変更判定に使うコンテキストを返す
+fn run_watch_cycle(root: &Path, since: Option<&str>, check_docs: bool) -> Option<ProjectContext> {
+    print!("\x1b[2J\x1b[H");
+    let context = run_analyze(root, since, check_docs, AnalyzeOutput::Human);
+    println!("\n👀 {} を監視中です（Ctrl-C で終了）", root.display());
+    let _ = io::stdout().flush();
+    context
+}
+
+/// 生成物・依存ディレクトリや`ignore_dirs`の配下だけの変更は無視する
+fn is_relevant_event(event: &notify::Result<notify::Event>, root: &Path, context: Option<&ProjectContext>) -> bool {
+    let Ok(event) = event else {
+        return false;
+    };
+    if event.kind.is_access() {
+        return false;
+    }
+    event.paths.iter().any(|path| match context {
+        Some(context) => !context.structure.is_excluded_path(root, path),
+        None => true,
+    })
+}
+
+/// 分析結果を表示し、ルートのコンテキスト（フルスタック構成のみならバックエンド）を返す。検出できなければNone
+fn run_analyze(target_path: &Path, since: Option<&str>, check_docs: bool, output: AnalyzeOutput) -> Option<ProjectContext> {
+    let (context, fullstack) = analyze_project(target_path, since, check_docs, output);
+    if output == AnalyzeOutput::Json {
+        if let Some(value) = analyze_json(target_path, context.as_ref(), fullstack.as_ref()) {
+            print_json(&value);
+        }
+    } else if let Some(layout) = &fullstack {
+        print_fullstack_areas(layout, output == AnalyzeOutput::SuggestOnly);
+    }
+    context.or_else(|| fullstack.map(|layout| layout.backend))
+}
+
+/// ルートを分析し、フルスタック構成なら各領域も検出する
+fn analyze_project(
+    target_path: &Path,
+    since: Option<&str>,
+    check_docs: bool,
+    output: AnalyzeOutput,
+) -> (Option<ProjectContext>, Option<FullStackLayout>) {
+    // 領域毎の分析は --since の絞り込みに対応していないため、その場合はルートのみ
+    let fullstack = if since.is_none() { ProjectDetector::detect_fullstack(target_path) } else { None };
+    let context = analyze_root(target_path, since, check_docs, output, fullstack.is_some());
+    (context, fullstack)
+}
+
+/// `analyze --json`の出力。フルスタック構成なら`fullstack`に各領域を含める。変換に失敗したらNone
+fn analyze_json(
+    target_path: &Path,
+    context: Option<&ProjectContext>,
+    fullstack: Option<&FullStackLayout>,
+) -> Option<serde_json::Value> {
+    if context.is_none() && fullstack.is_none() {
+        return Some(not_found_json(target_path));
+    }
+
+    let mut value = match context.map(serde_json::to_value) {
+        Some(Ok(value)) => value,
+        Some(Err(e)) => {
+            eprintln!("❌ JSONへの変換に失敗しました: {}", e);
+            return None;
+        }
+        None => serde_json::json!({}),
+    };
+    if let (Some(layout), Some(object)) = (fullstack, value.as_object_mut()) {
+        object.insert("fullstack".to_string(), serde_json::to_value(layout).unwrap_or_default());
+    }
+    Some(value)
+}
+
+fn print_json(value: &serde_json::Value) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("❌ JSONへの変換に失敗しました: {}", e),
+    }
+}
+
+/// プロジェクトを検出できなかったことを示すJSON（`--strict`と組み合わせてCIで判定する用）
+fn not_found_json(target_path: &Path) -> serde_json::Value {
+    serde_json::json!({
+        "error": "project_not_found",
+        "message": t!("detect.not_found"),
+        "path": target_path.display().to_string(),
+    })
+}
+
+/// フルスタック構成の各領域の分析結果（`--suggest-only`では`backend-`などを付けたコマンド名）を表示
+fn print_fullstack_areas(layout: &FullStackLayout, suggest_only: bool) {
+    if suggest_only {
+        for (area, context) in layout.areas() {
+            for command in &context.suggested_commands {
+                println!("{}-{}", area, command);
+            }
+        }
+        return;
+    }
+
+    println!("\n🧩 フルスタック構成（領域毎のコマンドは backend-*・frontend-* として生成されます）:");
+    for (area, context) in layout.areas() {
+        let relative = context.info.path.strip_prefix(layout_root(layout)).unwrap_or(&context.info.path);
+        println!("\n🗂️ {} ({}):", area, relative.display());
+        print_project_context(context);
+    }
+}
+
+/// 各領域の親ディレクトリ
+fn layout_root(layout: &FullStackLayout) -> &Path {
+    layout.backend.info.path.parent().unwrap_or(&layout.backend.info.path)
+}
+
+/// ルートのプロジェクトを分析。`has_areas`ならルートで検出できなくてもエラーにしない
+fn analyze_root(
+    target_path: &Path,
+    since: Option<&str>,
+    check_docs: bool,
+    output: AnalyzeOutput,
+    has_areas: bool,
+) -> Option<ProjectContext> {
+    match ProjectDetector::create_project_context(target_path) {
+        Some(mut context) => {
+            if let Some(git_ref) = since {
+                match git::changed_files_since(target_path, git_ref) {
+                    Ok(changed) => {
+                        ProjectDetector::scope_to_changed_files(&mut context, &changed);
+                        if output == AnalyzeOutput::Human {
+                            println!("🔀 {} からの変更ファイル {}個 を対象に分析します", git_ref, changed.len());
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("❌ git の変更ファイルを取得できませんでした: {}", e);
+                        return None;
+                    }
+                }
+            }
+            match output {
+                AnalyzeOutput::Human => {}
+                AnalyzeOutput::SuggestOnly => {
+                    for command in &context.suggested_commands {
+                        println!("{}", command);
+                    }
+                    return Some(context);
+                }
+                AnalyzeOutput::Json => return Some(context),
+            }
             println!("🔍 プロジェクト詳細分析結果:");
             print_project_context(&context);
+            if check_docs {
+                print_doc_reports(&docs::check_markdown_files(&context.structure.doc_files, &context.info.path));
+            }
+            Some(context)
+        }
+        None if has_areas || output == AnalyzeOutput::Json => None,
+        None if output == AnalyzeOutput::SuggestOnly => {
+            eprintln!("{}", t!("detect.not_found"));
+            None
         }
         None => {
-            println!("❓ 対応するプロジェクトタイプが見つかりませんでした");
+            println!("{}", t!("detect.not_found"));
+            None
+        }
+    }
+}
+
+fn print_doc_reports(reports: &[DocReport]) {
+    println!("\n📝 ドキュメントチェック:");
+    if reports.is_empty() {
+        println!("  Markdownファイルが見つかりませんでした");
+        return;
+    }
+
+    let mut issue_count = 0;
+    for report in reports.iter().filter(|r| !r.issues.is_empty()) {
+        println!("  {}:", report.path.display());
+        for issue in &report.issues {
+            match issue {
+                DocIssue::BrokenLink { target, line } => println!("    - リンク切れ: {} ({}行目)", target, line),
+                DocIssue::MissingSection(section) => println!("    - セクションがありません: {}", section),
+            }
         }
+        issue_count += report.issues.len();
+    }
+
+    if issue_count == 0 {
+        println!("  ✅ {} 個のファイルに問題は見つかりませんでした", reports.len());
+    } else {
+        println!("  ⚠️ {} 件の問題が見つかりました", issue_count);
     }
 }
 
@@ -189,31 +2582,118 @@ fn print_project_context(context: &ProjectContext) {
     let info = &context.info;
     let structure = &context.structure;
     
-    println!("📋 基本情報:");
-    println!("  名前: {}", info.name);
-    println!("  種別: {:?}", info.project_type);
-    println!("  パス: {}", info.path.display());
+    println!("{}", t!("analyze.basic_info"));
+    println!("{}", t!("detect.name", info.name));
+    if let Some(description) = &info.description {
+        println!("{}", t!("detect.description", description));
+    }
+    println!("{}", t!("detect.type", format!("{:?}", info.project_type)));
+    if let Some(framework) = &info.meta_framework {
+        println!("{}", t!("detect.framework", format!("{:?}", framework)));
+    }
+    if let Some(bundler) = &info.bundler {
+        println!("{}", t!("detect.bundler", format!("{:?}", bundler)));
+    }
+    println!("{}", t!("detect.path", info.path.display()));
     
     if !info.features.is_empty() {
-        println!("  機能: {}", info.features.join(", "));
+        println!("{}", t!("detect.features", info.features.join(", ")));
+    }
+    if let Some(edition) = &info.rust_edition {
+        println!("  Rust edition: {}", edition);
+    }
+    if let Some(msrv) = &info.rust_version {
+        println!("  MSRV: {}", msrv);
+    }
+    if !structure.readme_topics.is_empty() {
+        println!("  トピック: {}", structure.readme_topics.join(", "));
+    }
+    print_candidates(&ProjectDetector::score_candidates(&info.path));
+
+    if let Some(git) = &context.git {
+        println!("\n🌿 gitリポジトリ:");
+        println!("  ブランチ: {}", git.branch.as_deref().unwrap_or("(detached HEAD)"));
+        if let Some(remote) = &git.remote_url {
+            println!("  リモート(origin): {}", remote);
+        }
+        println!("  作業ツリー: {}", if git.dirty { "未コミットの変更あり" } else { "クリーン" });
+        if let Some(commit) = &git.last_commit {
+            println!("  最新コミット: {}", commit);
+        }
     }
 
     println!("\n📁 ファイル構成:");
     println!("  ソースファイル: {}個", structure.source_files.len());
     println!("  テストファイル: {}個", structure.test_files.len());
+    if !structure.example_files.is_empty() {
+        println!("  サンプル（examples/）: {}個", structure.example_files.len());
+    }
+    if !structure.bench_files.is_empty() {
+        println!("  ベンチマーク（benches/）: {}個", structure.bench_files.len());
+    }
+    if !structure.generated_files.is_empty() {
+        println!("  生成ファイル: {}個（--include-generated で通常のファイルとして数えます）", structure.generated_files.len());
+    }
     println!("  設定ファイル: {}個", structure.config_files.len());
     println!("  ドキュメント: {}個", structure.doc_files.len());
+    match &structure.readme {
+        Some(readme) => println!("  README: あり ({})", readme.file_name().unwrap_or_default().to_string_lossy()),
+        None => println!("  README: なし"),
+    }
+    if !structure.license_files.is_empty() {
+        let files: Vec<String> = structure
+            .license_files
+            .iter()
+            .map(|p| p.file_name().unwrap_or_default().to_string_lossy().to_string())
+            .collect();
+        let license = structure.detect_license().unwrap_or_else(|| "不明".to_string());
+        println!("  ライセンス: {} ({})", license, files.join(", "));
+    } else {
+        println!("  ライセンス: なし");
+    }
     println!("  エントリーポイント: {}個", structure.entry_points.len());
+    if !structure.schema_files.is_empty() {
+        println!("  スキーマファイル: {}個", structure.schema_files.len());
+    }
+    if let Some(spec) = structure.detect_api_spec() {
+        let title = spec.title.as_deref().unwrap_or("タイトルなし");
+        match &spec.version {
+            Some(version) => println!("  API仕様: {} ({} v{})", spec.path.display(), title, version),
+            None => println!("  API仕様: {} ({})", spec.path.display(), title),
+        }
+    }
+    if let Some((path, modified)) = structure.most_recently_modified(&structure.source_files) {
+        let days = modified.elapsed().map(|d| d.as_secs() / 86400).unwrap_or(0);
+        println!("  最近更新されたソースファイル: {} ({}日前)", path.display(), days);
+    }
+
+    if !structure.loc_by_extension.is_empty() {
+        println!("\n📏 コード行数:");
+        let mut loc: Vec<_> = structure.loc_by_extension.iter().collect();
+        loc.sort_by(|(a_ext, a), (b_ext, b)| b.cmp(a).then_with(|| a_ext.cmp(b_ext)));
+        for (extension, lines) in loc {
+            println!("  .{}: {}行", extension, lines);
+        }
+    }
 
     if !structure.dependencies.is_empty() {
+        let (limit, sort) = *DEPS_VIEW.get_or_init(|| (Some(DEFAULT_DEPS_LIMIT), DepsSort::Name));
         println!("\n📦 主要な依存関係:");
         let mut deps: Vec<_> = structure.dependencies.iter().collect();
         deps.sort_by_key(|(name, _)| name.as_str());
-        for (name, version) in deps.iter().take(10) {
-            println!("  {} = {}", name, version);
+        let counts = (sort == DepsSort::Count).then(|| structure.dependency_reference_counts());
+        if let Some(counts) = &counts {
+            deps.sort_by_key(|(name, _)| std::cmp::Reverse(counts.get(*name).copied().unwrap_or(0)));
+        }
+        let shown = limit.unwrap_or(deps.len()).min(deps.len());
+        for (name, version) in &deps[..shown] {
+            match counts.as_ref().map(|counts| counts.get(*name).copied().unwrap_or(0)) {
+                Some(count) => println!("  {} = {} ({}ファイルで参照)", name, version, count),
+                None => println!("  {} = {}", name, version),
+            }
         }
-        if structure.dependencies.len() > 10 {
-            println!("  ... 他{}個", structure.dependencies.len() - 10);
+        if deps.len() > shown {
+            println!("  ... 他{}個（--all-deps で全て表示）", deps.len() - shown);
         }
     }
 
@@ -1,30 +1,245 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
 use dirs::home_dir;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::error::CcmgenError;
+use crate::fsutil::{backup_file, write_atomic};
+
+/// 現在の設定ファイルの形式バージョン（`config migrate`で更新）
+pub const CONFIG_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Config {
+    /// 設定ファイルの形式バージョン（無い場合は0 = バージョン管理以前）
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub custom_templates: HashMap<String, Vec<CustomTemplate>>,
+    #[serde(default)]
+    pub default_settings: DefaultSettings,
+    /// 生成・提案から除外するテンプレート名
+    #[serde(default)]
+    pub disabled_templates: Vec<String>,
+    /// エイリアス → 実際のコマンド名
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+    /// `--profile`/`CCMGEN_PROFILE`で切り替える名前付きプロファイル
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub profiles: HashMap<String, Profile>,
+    /// `init --from-config`で生成するテンプレート
+    #[serde(default, skip_serializing_if = "InitConfig::is_empty")]
+    pub init: InitConfig,
+    /// 生成するコマンド名のプレフィックス（`{lang}`、`{project}`を展開）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_prefix: Option<String>,
+    /// `init --run-hooks`で生成後に実行するシェルコマンド
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_init_hook: Option<String>,
+    /// 言語キー（`rust`など）毎に、素の`init`で生成する組み込みテンプレート（未指定なら全て）
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub defaults: HashMap<String, TypeDefaults>,
+    /// プロジェクト検出器の優先順位（`unity`、`rust`、`javascript`、`r`、`haskell`、`scala`、`python`。未指定のものは既定の順番で後ろに続く）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub detector_order: Vec<String>,
+    /// 生成するコマンドに埋め込むプロジェクト情報の最大文字数（未指定なら800、0で無制限）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_context_chars: Option<usize>,
+    /// `init --preset`のプリセット名 → テンプレート名（組み込みの同名プリセットを上書き、`*`は全テンプレート）
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub presets: HashMap<String, Vec<String>>,
+    /// 拡張子（先頭の`.`は省略可）→ 分類。組み込みの分類より優先する
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub file_categories: HashMap<String, FileCategory>,
+    /// 推奨コマンドを調整するプロジェクト規模の閾値
+    #[serde(default, skip_serializing_if = "ProjectSizeConfig::is_empty")]
+    pub project_size: ProjectSizeConfig,
+    /// 組み込み・カスタムなど複数の出どころに同名のテンプレートがある場合の扱い
+    #[serde(default, skip_serializing_if = "ConflictPolicy::is_default")]
+    pub conflict_policy: ConflictPolicy,
+    /// コマンドファイルの拡張子（未指定なら`md`、空文字なら拡張子なし。`--commands-ext`が優先）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commands_ext: Option<String>,
+    /// `init`時にプロジェクト情報（JSON）を標準入力で渡し、`[{"name", "body"}]`を標準出力から受け取る外部プログラム（相対パスは設定ファイルのディレクトリ基準）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_provider: Option<String>,
+}
+
+/// ソース・テストファイル数によるプロジェクト規模の閾値
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectSizeConfig {
+    /// これ以下なら小規模（README・テストの雛形を優先。未指定なら10）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub small_max_files: Option<usize>,
+    /// これ以上なら大規模（パフォーマンス・メモリ・設計のレビューを優先。未指定なら500）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub large_min_files: Option<usize>,
+}
+
+impl ProjectSizeConfig {
+    pub fn is_empty(&self) -> bool {
+        self.small_max_files.is_none() && self.large_min_files.is_none()
+    }
+}
+
+/// プロジェクト構造の分析で使うファイルの分類
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FileCategory {
+    /// ソース（テストの判定・行数の計測も行う）
+    Source,
+    Config,
+    Doc,
+    Schema,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct Profile {
+    #[serde(default)]
     pub custom_templates: HashMap<String, Vec<CustomTemplate>>,
+    #[serde(default)]
     pub default_settings: DefaultSettings,
+    #[serde(default)]
+    pub disabled_templates: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct InitConfig {
+    /// 生成するテンプレート名（この順番で生成）
+    #[serde(default)]
+    pub templates: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TypeDefaults {
+    /// 生成する組み込みテンプレート名
+    #[serde(default)]
+    pub templates: Vec<String>,
+}
+
+impl InitConfig {
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+}
+
+/// リポジトリ直下の`.ccmgen.toml`（プロジェクト毎の設定）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectConfig {
+    /// 自動検出の代わりに使うプロジェクト種別（`RustWasm`などの種別名または`rust`などの言語キー）
+    #[serde(default)]
+    pub project_type: Option<String>,
+    /// このプロジェクトで追加生成するテンプレート（同名のテンプレートを上書き）
+    #[serde(default)]
+    pub templates: Vec<ProjectTemplate>,
+    /// スキャン対象外にするディレクトリ（ディレクトリ名またはルートからの相対パス）
+    #[serde(default)]
+    pub ignore_dirs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CustomTemplate {
     pub name: String,
     pub description: String,
     pub content: String,
     pub language: Option<String>,
     pub project_type: Option<String>,
+    /// 作成者（生成するファイルのフロントマターに書き込む）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// テンプレートのバージョン（生成するファイルのフロントマターに書き込む）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+/// テンプレート・生成したコマンドの作成者とバージョン
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TemplateMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl TemplateMetadata {
+    /// ファイル先頭のフロントマターの`author`・`version`を読む
+    pub fn from_content(content: &str) -> Self {
+        let (frontmatter, _) = parse_frontmatter(content);
+        Self {
+            author: frontmatter.and_then(|f| frontmatter_value(f, "author")),
+            version: frontmatter.and_then(|f| frontmatter_value(f, "version")),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.author.is_none() && self.version.is_none()
+    }
+
+    /// フロントマターとして書き込む文字列（どちらも無ければ空文字）
+    pub fn to_frontmatter(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+        let mut frontmatter = String::from("---\n");
+        for (key, value) in [("author", &self.author), ("version", &self.version)] {
+            if let Some(value) = value {
+                frontmatter.push_str(&format!("{}: \"{}\"\n", key, value.replace('\\', "\\\\").replace('"', "\\\"")));
+            }
+        }
+        frontmatter.push_str("---\n\n");
+        frontmatter
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DefaultSettings {
     pub auto_detect: bool,
     pub prefer_typescript: bool,
     pub include_tests: bool,
     pub include_docs: bool,
+    /// `generate-commit-message`のスタイル
+    #[serde(default)]
+    pub commit_style: CommitStyle,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitStyle {
+    #[default]
+    Conventional,
+    Plain,
+}
+
+/// 同名のテンプレートが複数の出どころにある場合の扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConflictPolicy {
+    /// 後から読み込む出どころ（カスタム → template_provider → .ccmgen.toml）を採用して警告する
+    #[default]
+    CustomWins,
+    /// 組み込み・プロジェクト固有の提案を採用して警告する
+    BuiltinWins,
+    /// 衝突があれば生成を中止する
+    Error,
+}
+
+impl ConflictPolicy {
+    pub fn is_default(&self) -> bool {
+        *self == ConflictPolicy::default()
+    }
 }
 
 impl Default for DefaultSettings {
@@ -34,72 +249,204 @@ impl Default for DefaultSettings {
             prefer_typescript: true,
             include_tests: true,
             include_docs: true,
+            commit_style: CommitStyle::default(),
         }
     }
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            custom_templates: HashMap::new(),
-            default_settings: DefaultSettings::default(),
-        }
-    }
+pub struct ConfigManager;
+
+/// 初回の`claude_dir`で解決した`~/.claude`（ホームディレクトリを特定できなければNone）
+static CLAUDE_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// `~/.claude`ディレクトリ。ホームディレクトリの解決はプロセス内で一度だけ行う
+pub fn claude_dir() -> Result<PathBuf, CcmgenError> {
+    CLAUDE_DIR
+        .get_or_init(|| home_dir().map(|home| home.join(".claude")))
+        .clone()
+        .ok_or(CcmgenError::HomeDirUnavailable)
 }
 
-pub struct ConfigManager;
+/// TOMLファイルを読み込む（ファイルが無ければNone）
+fn read_toml<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, CcmgenError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    toml::from_str(&content)
+        .map(Some)
+        .map_err(|source| CcmgenError::ConfigParse { path: path.to_path_buf(), source })
+}
+
+/// リポジトリ直下に置くプロジェクト毎の設定ファイル名
+pub const PROJECT_CONFIG_FILE: &str = ".ccmgen.toml";
+
+/// `--profile`で指定されたプロファイル名
+static ACTIVE_PROFILE: OnceLock<String> = OnceLock::new();
+
+/// プロファイルが見つからない警告を表示済みか（`load_config`は何度も呼ばれるため1回だけ表示する）
+static PROFILE_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// 設定ファイルの読み込み・形式の警告を表示済みか
+static CONFIG_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// 読み込みの警告を表示済みの`.ccmgen.toml`
+static PROJECT_CONFIG_WARNED: Mutex<Option<HashSet<PathBuf>>> = Mutex::new(None);
 
 impl ConfigManager {
-    pub fn get_config_path() -> PathBuf {
-        home_dir()
-            .expect("Could not get home directory")
-            .join(".claude")
-            .join("ccmgen.toml")
+    /// `--profile`で指定されたプロファイルを設定（`CCMGEN_PROFILE`より優先）
+    pub fn set_active_profile(name: String) {
+        let _ = ACTIVE_PROFILE.set(name);
+    }
+
+    pub fn active_profile() -> Option<String> {
+        ACTIVE_PROFILE
+            .get()
+            .cloned()
+            .or_else(|| std::env::var("CCMGEN_PROFILE").ok().filter(|p| !p.is_empty()))
+    }
+
+    pub fn get_config_path() -> Result<PathBuf, CcmgenError> {
+        Ok(claude_dir()?.join("ccmgen.toml"))
     }
 
+    /// 設定を読み込む。プロファイルが選択されていればその内容を返す
     pub fn load_config() -> Config {
-        let config_path = Self::get_config_path();
-        
-        if !config_path.exists() {
-            return Config::default();
+        let config = Self::load_base_config();
+
+        match Self::active_profile() {
+            Some(name) => Self::apply_profile(config, &name),
+            None => config,
         }
+    }
 
-        match fs::read_to_string(&config_path) {
-            Ok(content) => {
-                toml::from_str(&content).unwrap_or_else(|e| {
-                    eprintln!("⚠️ 設定ファイルの読み込みに失敗しました: {}", e);
-                    Config::default()
-                })
+    fn apply_profile(mut config: Config, name: &str) -> Config {
+        match config.profiles.get(name).cloned() {
+            Some(profile) => {
+                config.custom_templates = profile.custom_templates;
+                config.default_settings = profile.default_settings;
+                config.disabled_templates = profile.disabled_templates;
+                config
             }
+            None => {
+                if !PROFILE_WARNED.swap(true, Ordering::Relaxed) {
+                    eprintln!("⚠️ プロファイルが見つかりません: {}（トップレベルの設定を使用します）", name);
+                }
+                config
+            }
+        }
+    }
+
+    fn load_base_config() -> Config {
+        match Self::get_config_path().and_then(|path| read_toml::<Config>(&path)) {
+            Ok(Some(config)) => {
+                if config.version > CONFIG_VERSION && !CONFIG_WARNED.swap(true, Ordering::Relaxed) {
+                    eprintln!("⚠️ 設定ファイルはより新しい ccmgen の形式です (version {})", config.version);
+                }
+                config
+            }
+            Ok(None) => Config::default(),
             Err(e) => {
-                eprintln!("⚠️ 設定ファイルの読み込みに失敗しました: {}", e);
+                if !CONFIG_WARNED.swap(true, Ordering::Relaxed) {
+                    eprintln!("⚠️ {}", e);
+                    if matches!(e, CcmgenError::ConfigParse { .. }) {
+                        eprintln!("💡 古い形式の場合は ccmgen config migrate で更新できます");
+                    }
+                }
                 Config::default()
             }
         }
     }
 
-    pub fn save_config(config: &Config) -> Result<(), std::io::Error> {
-        let config_path = Self::get_config_path();
+    /// プロジェクト直下の`.ccmgen.toml`を読み込む（無ければNone）
+    pub fn read_project_config(project_path: &Path) -> Result<Option<ProjectConfig>, CcmgenError> {
+        read_toml(&project_path.join(PROJECT_CONFIG_FILE))
+    }
+
+    /// `read_project_config`の読み込みエラーを警告してNoneとして扱う（警告はファイル毎に1回だけ）
+    pub fn load_project_config(project_path: &Path) -> Option<ProjectConfig> {
+        Self::read_project_config(project_path).unwrap_or_else(|e| {
+            let mut warned = PROJECT_CONFIG_WARNED.lock().unwrap_or_else(|e| e.into_inner());
+            if warned.get_or_insert_with(HashSet::new).insert(project_path.join(PROJECT_CONFIG_FILE)) {
+                eprintln!("⚠️ {}", e);
+            }
+            None
+        })
+    }
+
+    /// 設定ファイルを現在の形式に更新する
+    ///
+    /// 既存の内容をデフォルト値の上に重ねて読み込み（不足している項目はデフォルト値で補完）、
+    /// 元のファイルを`ccmgen.toml.bak`（既にあれば`.bak.1`など）に退避してから書き込む。
+    /// 既に現在の形式なら書き換えず、より新しい形式ならエラー
+    pub fn migrate_config() -> Result<MigrationOutcome, CcmgenError> {
+        let config_path = Self::get_config_path()?;
+        let content = fs::read_to_string(&config_path)?;
+        let parse_error = |source| CcmgenError::ConfigParse { path: config_path.clone(), source };
+        let original: toml::Value = toml::from_str(&content).map_err(parse_error)?;
+
+        let mut merged = toml::Value::try_from(Config::default())?;
+        merge_toml(&mut merged, original.clone());
+        let mut config: Config = merged.try_into().map_err(parse_error)?;
+        let from_version = config.version;
+        if from_version > CONFIG_VERSION {
+            return Err(CcmgenError::ConfigTooNew { path: config_path, version: from_version });
+        }
+        if from_version == CONFIG_VERSION {
+            return Ok(MigrationOutcome::UpToDate);
+        }
+        config.version = CONFIG_VERSION;
+
+        let upgraded = toml::to_string_pretty(&config)?;
+
+        let retained: toml::Value = toml::from_str(&upgraded).map_err(parse_error)?;
+        let dropped_keys = original
+            .as_table()
+            .map(|table| {
+                table
+                    .keys()
+                    .filter(|key| retained.get(key.as_str()).is_none())
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let backup_path = backup_file(&config_path)?;
+        write_atomic(&config_path, &upgraded)?;
+
+        Ok(MigrationOutcome::Migrated {
+            from_version,
+            backup_path,
+            dropped_keys,
+        })
+    }
+
+    pub fn save_config(config: &Config) -> Result<(), CcmgenError> {
+        let config_path = Self::get_config_path()?;
         
         // ディレクトリが存在しない場合は作成
         if let Some(parent) = config_path.parent() {
             fs::create_dir_all(parent)?;
         }
 
-        let content = toml::to_string_pretty(config)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        let content = toml::to_string_pretty(config)?;
         
         fs::write(&config_path, content)?;
         Ok(())
     }
 
+    /// カスタムテンプレートを追加して保存する。プロファイルが選択されていればそのプロファイルに追加する
+    ///
+    /// プロファイルの内容をトップレベルに書き込まないよう、プロファイルを適用する前の設定を保存する
+    #[allow(dead_code)]
     pub fn add_custom_template(
         language: &str,
         name: &str,
         description: &str,
         content: &str,
-    ) -> Result<(), std::io::Error> {
-        let mut config = Self::load_config();
+    ) -> Result<(), CcmgenError> {
+        let mut config = Self::load_base_config();
         
         let template = CustomTemplate {
             name: name.to_string(),
@@ -107,30 +454,195 @@ impl ConfigManager {
             content: content.to_string(),
             language: Some(language.to_string()),
             project_type: None,
+            author: None,
+            version: None,
         };
 
-        config
-            .custom_templates
+        let profile = Self::active_profile().and_then(|name| config.profiles.get_mut(&name));
+        let custom_templates = match profile {
+            Some(profile) => &mut profile.custom_templates,
+            None => &mut config.custom_templates,
+        };
+        custom_templates
             .entry(language.to_string())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(template);
 
         Self::save_config(&config)
     }
 
+    pub fn get_templates_dir() -> Result<PathBuf, CcmgenError> {
+        Ok(claude_dir()?.join("templates"))
+    }
+
+    /// 言語毎のカスタムテンプレートを取得
+    ///
+    /// 優先順位: `~/.claude/templates/<language>/<name>.md` > 設定ファイルの`custom_templates`
     pub fn get_custom_templates_for_language(language: &str) -> Vec<CustomTemplate> {
         let config = Self::load_config();
-        config
+        let mut templates = config
             .custom_templates
             .get(language)
             .cloned()
-            .unwrap_or_default()
+            .unwrap_or_default();
+
+        for template in Self::load_directory_templates(language) {
+            match templates.iter_mut().find(|t| t.name == template.name) {
+                Some(existing) => *existing = template,
+                None => templates.push(template),
+            }
+        }
+
+        templates
     }
 
-    pub fn create_default_config() -> Result<(), std::io::Error> {
-        let config = Config::default();
+    /// カスタムテンプレートが定義されている言語（設定ファイルとテンプレートディレクトリ）
+    pub fn custom_template_languages() -> Vec<String> {
+        let mut languages: Vec<String> = Self::load_config().custom_templates.into_keys().collect();
+        if let Ok(entries) = Self::get_templates_dir().and_then(|dir| Ok(fs::read_dir(dir)?)) {
+            for entry in entries.flatten() {
+                if entry.path().is_dir() {
+                    languages.push(entry.file_name().to_string_lossy().to_string());
+                }
+            }
+        }
+        languages.sort();
+        languages.dedup();
+        languages
+    }
+
+    /// `~/.claude/templates/<language>/*.md`をカスタムテンプレートとして読み込む
+    fn load_directory_templates(language: &str) -> Vec<CustomTemplate> {
+        let Ok(dir) = Self::get_templates_dir().map(|dir| dir.join(language)) else {
+            return Vec::new();
+        };
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return Vec::new();
+        };
+
+        let mut templates: Vec<CustomTemplate> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension().is_some_and(|e| e == "md"))
+            .filter_map(|path| {
+                let name = path.file_stem()?.to_string_lossy().to_string();
+                let content = match fs::read_to_string(&path) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        eprintln!("⚠️ テンプレートの読み込みに失敗しました: {}: {}", path.display(), e);
+                        return None;
+                    }
+                };
+                let metadata = TemplateMetadata::from_content(&content);
+                let (description, content) = split_frontmatter(&content);
+                Some(CustomTemplate {
+                    name,
+                    description: description.unwrap_or_default(),
+                    content: content.trim_end().to_string(),
+                    language: Some(language.to_string()),
+                    project_type: None,
+                    author: metadata.author,
+                    version: metadata.version,
+                })
+            })
+            .collect();
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+
+        templates
+    }
+
+    /// エイリアスを実際のコマンド名に解決。エイリアスでなければそのまま返す
+    pub fn resolve_alias(name: &str) -> Option<String> {
+        Self::load_config().aliases.get(name).cloned()
+    }
+
+    /// `[defaults.<language>]`で組み込みテンプレートの既定セットが指定されていればそれを返す
+    pub fn default_template_subset<'a>(config: &'a Config, language: &str) -> Option<&'a [String]> {
+        config
+            .defaults
+            .get(language)
+            .map(|defaults| defaults.templates.as_slice())
+            .filter(|templates| !templates.is_empty())
+    }
+
+    pub fn is_template_disabled(config: &Config, name: &str) -> bool {
+        config.disabled_templates.iter().any(|t| t == name)
+    }
+
+    /// 設定ファイルのJSON Schemaを生成
+    pub fn config_schema() -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&schemars::schema_for!(Config))
+    }
+
+    pub fn create_default_config() -> Result<(), CcmgenError> {
+        let config = Config {
+            version: CONFIG_VERSION,
+            ..Config::default()
+        };
         Self::save_config(&config)?;
-        println!("✅ デフォルト設定ファイルを作成しました: {}", Self::get_config_path().display());
+        println!("✅ デフォルト設定ファイルを作成しました: {}", Self::get_config_path()?.display());
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// `config migrate`の結果
+pub enum MigrationOutcome {
+    /// 既に現在の形式
+    UpToDate,
+    Migrated {
+        from_version: u32,
+        backup_path: PathBuf,
+        /// 現在の形式に無いため削除した項目
+        dropped_keys: Vec<String>,
+    },
+}
+
+/// `overlay`のテーブルを`base`に再帰的に重ねる（`overlay`の値を優先）
+fn merge_toml(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// 先頭の`---`で囲まれたフロントマターから`description`を取り出し、本文と分ける
+///
+/// フロントマターが無ければ内容全体を本文として返す
+pub fn split_frontmatter(content: &str) -> (Option<String>, &str) {
+    let (frontmatter, body) = parse_frontmatter(content);
+    (frontmatter.and_then(|f| frontmatter_value(f, "description")), body)
+}
+
+/// 先頭の`---`で囲まれたフロントマター（区切り線を除く）と本文に分ける
+pub fn parse_frontmatter(content: &str) -> (Option<&str>, &str) {
+    let Some(rest) = content.strip_prefix("---\n") else {
+        return (None, content);
+    };
+    let Some(end) = rest.find("\n---") else {
+        return (None, content);
+    };
+
+    let body = rest[end + 4..].trim_start_matches(['\r', '\n']);
+    (Some(&rest[..end]), body)
+}
+
+/// フロントマター中の`key: value`の値（ダブルクォートで囲まれていれば外す）
+fn frontmatter_value(frontmatter: &str, key: &str) -> Option<String> {
+    frontmatter.lines().find_map(|line| {
+        let value = line.strip_prefix(key)?.strip_prefix(':')?.trim();
+        let unquoted = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .map(|v| v.replace("\\\"", "\"").replace("\\\\", "\\"));
+        Some(unquoted.unwrap_or_else(|| value.to_string()))
+    })
+}
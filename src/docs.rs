@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// READMEに期待する標準セクション（見出しに含まれていればよい語の候補）
+const README_SECTIONS: &[(&str, &[&str])] = &[
+    ("Installation", &["installation", "install", "インストール"]),
+    ("Usage", &["usage", "使い方", "使用方法"]),
+    ("License", &["license", "licence", "ライセンス"]),
+];
+
+/// キーワード抽出で除外する一般的な語
+const STOP_WORDS: &[&str] = &[
+    "about", "after", "also", "before", "build", "code", "example", "examples", "file", "files",
+    "from", "have", "installation", "install", "into", "license", "more", "other", "project",
+    "run", "should", "some", "such", "than", "that", "their", "then", "there", "these", "this",
+    "usage", "used", "using", "when", "which", "will", "with", "your", "you",
+];
+
+/// 見出し中の語の重み（本文の語は1）
+const HEADING_WEIGHT: usize = 3;
+
+/// ドキュメントファイル毎のチェック結果
+#[derive(Debug, Clone)]
+pub struct DocReport {
+    pub path: PathBuf,
+    pub issues: Vec<DocIssue>,
+}
+
+#[derive(Debug, Clone)]
+pub enum DocIssue {
+    /// 相対リンクの参照先が存在しない
+    BrokenLink { target: String, line: usize },
+    /// READMEに標準セクションが無い
+    MissingSection(&'static str),
+}
+
+/// Markdownファイルのリンク切れとREADMEの標準セクションをチェック
+///
+/// `root`はプロジェクトルート。`/`始まりのリンクはルートからのパスとして解決する
+pub fn check_markdown_files(files: &[PathBuf], root: &Path) -> Vec<DocReport> {
+    let mut files: Vec<&PathBuf> = files
+        .iter()
+        .filter(|p| p.extension().is_some_and(|e| e == "md"))
+        .collect();
+    files.sort();
+
+    files
+        .into_iter()
+        .filter_map(|path| {
+            // 非UTF-8の部分は置換文字にして読む
+            let bytes = fs::read(path).ok()?;
+            let content = String::from_utf8_lossy(&bytes);
+            let mut issues = broken_links(&content, path, root);
+            if is_root_readme(path, root) {
+                issues.extend(missing_readme_sections(&content));
+            }
+            Some(DocReport {
+                path: path.clone(),
+                issues,
+            })
+        })
+        .collect()
+}
+
+fn is_root_readme(path: &Path, root: &Path) -> bool {
+    path.parent() == Some(root)
+        && path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.eq_ignore_ascii_case("readme.md"))
+}
+
+fn broken_links(content: &str, path: &Path, root: &Path) -> Vec<DocIssue> {
+    let base_dir = path.parent().unwrap_or(root);
+    let mut issues = Vec::new();
+    let mut in_code_block = false;
+
+    for (index, line) in content.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        for target in link_targets(line) {
+            let Some(file) = local_link_path(&target) else {
+                continue;
+            };
+            let resolved = match file.strip_prefix('/') {
+                Some(from_root) => root.join(from_root),
+                None => base_dir.join(file),
+            };
+            if !resolved.exists() {
+                issues.push(DocIssue::BrokenLink {
+                    target,
+                    line: index + 1,
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+/// 行内の`[text](target)`からリンク先を取り出す（インラインコード内は除く）
+fn link_targets(line: &str) -> Vec<String> {
+    let mut targets = Vec::new();
+    let mut in_code = false;
+    let mut rest = line;
+
+    while let Some(pos) = rest.find(['`', ']']) {
+        let (matched, after) = (&rest[pos..pos + 1], &rest[pos + 1..]);
+        if matched == "`" {
+            in_code = !in_code;
+            rest = after;
+            continue;
+        }
+        if !in_code
+            && let Some(link) = after.strip_prefix('(')
+            && let Some(end) = link.find(')')
+        {
+            // `[text](path "title")` のタイトル部分は除く
+            let target = link[..end].split_whitespace().next().unwrap_or_default();
+            if !target.is_empty() {
+                targets.push(target.to_string());
+            }
+        }
+        rest = after;
+    }
+
+    targets
+}
+
+/// ローカルファイルへのリンクであればアンカーを除いたパスを返す
+fn local_link_path(target: &str) -> Option<&str> {
+    if target.starts_with('#') || target.contains("://") || target.starts_with("mailto:") {
+        return None;
+    }
+    let file = target.split(['#', '?']).next().unwrap_or_default();
+    (!file.is_empty()).then_some(file)
+}
+
+fn missing_readme_sections(content: &str) -> Vec<DocIssue> {
+    let headings: Vec<String> = content
+        .lines()
+        .filter(|line| line.starts_with('#'))
+        .map(|line| line.trim_start_matches('#').trim().to_lowercase())
+        .collect();
+
+    README_SECTIONS
+        .iter()
+        .filter(|(_, keywords)| {
+            !headings
+                .iter()
+                .any(|heading| keywords.iter().any(|k| heading.contains(k)))
+        })
+        .map(|(section, _)| DocIssue::MissingSection(section))
+        .collect()
+}
+
+/// READMEの見出しと本文から重み付きでキーワードを抽出し、上位`limit`件を返す
+///
+/// 見出しの語は本文より重く数える。コードブロック・リンク先・短い語・一般的な語は除く
+pub fn readme_keywords(content: &str, limit: usize) -> Vec<String> {
+    let mut scores: HashMap<String, usize> = HashMap::new();
+    let mut in_code_block = false;
+
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        let weight = if line.starts_with('#') { HEADING_WEIGHT } else { 1 };
+        for word in words(&strip_link_targets(line)) {
+            *scores.entry(word).or_default() += weight;
+        }
+    }
+
+    let mut ranked: Vec<(String, usize)> = scores.into_iter().collect();
+    ranked.sort_by(|(a, a_score), (b, b_score)| b_score.cmp(a_score).then_with(|| a.cmp(b)));
+    ranked.into_iter().take(limit).map(|(word, _)| word).collect()
+}
+
+/// READMEの最初の段落（見出し・バッジ・コードブロックを除く）を`max_chars`文字までで返す
+pub fn readme_summary(content: &str, max_chars: usize) -> Option<String> {
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut in_code_block = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        // `Title` + `=====` の形式（Markdownのsetext見出し・reStructuredTextの見出し）
+        if trimmed.len() >= 3 && trimmed.chars().all(|c| "=-~^*+#".contains(c)) {
+            paragraph.clear();
+            continue;
+        }
+
+        let is_text = !trimmed.is_empty()
+            && !trimmed.starts_with('#')
+            && !trimmed.starts_with("[![")
+            && !trimmed.starts_with("![")
+            && !trimmed.starts_with('<')
+            && !trimmed.starts_with('|');
+        if is_text {
+            paragraph.push(trimmed);
+        } else if !paragraph.is_empty() {
+            break;
+        }
+    }
+
+    if paragraph.is_empty() {
+        return None;
+    }
+    let summary = paragraph.join(" ");
+    if summary.chars().count() <= max_chars {
+        return Some(summary);
+    }
+    let truncated: String = summary.chars().take(max_chars).collect();
+    Some(format!("{}…", truncated.trim_end()))
+}
+
+/// `[text](target)`のリンク先部分を取り除く
+fn strip_link_targets(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(pos) = rest.find("](") {
+        result.push_str(&rest[..pos]);
+        rest = match rest[pos..].find(')') {
+            Some(end) => &rest[pos + end + 1..],
+            None => "",
+        };
+    }
+    result.push_str(rest);
+    result
+}
+
+/// 英数字（ASCII以外の文字を含む）の語を小文字で返す
+///
+/// ASCIIの語は4文字以上、漢字などを含む語は2文字以上とし、数字だけ・ひらがなだけ（助詞や語尾）の語と一般的な語を除く
+fn words(line: &str) -> impl Iterator<Item = String> + '_ {
+    line.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+        .map(|word| word.trim_matches(['-', '_']).to_lowercase())
+        .filter(|word| {
+            let min_chars = if word.is_ascii() { 4 } else { 2 };
+            word.chars().count() >= min_chars
+                && !word.chars().all(char::is_numeric)
+                && !word.chars().all(|c| ('\u{3041}'..='\u{309f}').contains(&c))
+                && !STOP_WORDS.contains(&word.as_str())
+        })
+}
@@ -0,0 +1,83 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// ccmgenの処理で発生するエラー
+///
+/// 呼び出し側で失敗の種類（環境・設定・マニフェスト・git・IO）を区別できるようにする
+#[derive(Debug)]
+pub enum CcmgenError {
+    /// ホームディレクトリを特定できない
+    HomeDirUnavailable,
+    /// 設定ファイル（`~/.claude/ccmgen.toml`・`.ccmgen.toml`）の構文・型の誤り
+    ConfigParse { path: PathBuf, source: toml::de::Error },
+    /// 設定をTOMLに書き出せない
+    ConfigSerialize(toml::ser::Error),
+    /// マニフェスト（`Cargo.toml`・`package.json`など）を解析できない
+    ManifestParse { path: PathBuf, message: String },
+    /// gitコマンドの失敗
+    Git { command: String, message: String },
+    /// リモートリポジトリのクローン・プッシュの失敗
+    NetworkFetch { url: String, message: String },
+    /// 別のccmgenプロセスがロックを保持したまま待ち時間を超えた
+    LockTimeout(PathBuf),
+    /// 設定ファイルがこのccmgenより新しい形式
+    ConfigTooNew { path: PathBuf, version: u32 },
+    /// `conflict_policy = "error"`で同名のテンプレートが複数の出どころにあった
+    TemplateConflict(Vec<String>),
+    Io(io::Error),
+}
+
+impl fmt::Display for CcmgenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CcmgenError::HomeDirUnavailable => write!(f, "ホームディレクトリを特定できません（HOME を設定してください）"),
+            CcmgenError::ConfigParse { path, source } => write!(f, "{} の読み込みに失敗しました: {}", path.display(), source),
+            CcmgenError::ConfigSerialize(e) => write!(f, "設定をTOMLに変換できませんでした: {}", e),
+            CcmgenError::ManifestParse { path, message } => write!(f, "{} を解析できませんでした: {}", path.display(), message),
+            CcmgenError::Git { command, message } => write!(f, "git {} に失敗しました: {}", command, message),
+            CcmgenError::NetworkFetch { url, message } => write!(f, "{} との通信に失敗しました: {}", url, message),
+            CcmgenError::LockTimeout(path) => write!(
+                f,
+                "別の ccmgen プロセスが実行中です（残っている場合は {} を削除してください）",
+                path.display()
+            ),
+            CcmgenError::ConfigTooNew { path, version } => write!(
+                f,
+                "{} はより新しい ccmgen の形式です (version {}、このバージョンは {} まで対応)。ccmgen を更新してください",
+                path.display(),
+                version,
+                crate::config::CONFIG_VERSION
+            ),
+            CcmgenError::TemplateConflict(names) => write!(
+                f,
+                "同名のテンプレートが複数あります（conflict_policy = \"error\"）: {}",
+                names.join(", ")
+            ),
+            CcmgenError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for CcmgenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CcmgenError::ConfigParse { source, .. } => Some(source),
+            CcmgenError::ConfigSerialize(e) => Some(e),
+            CcmgenError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CcmgenError {
+    fn from(e: io::Error) -> Self {
+        CcmgenError::Io(e)
+    }
+}
+
+impl From<toml::ser::Error> for CcmgenError {
+    fn from(e: toml::ser::Error) -> Self {
+        CcmgenError::ConfigSerialize(e)
+    }
+}
@@ -0,0 +1,52 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// 同じディレクトリの一時ファイルに書き込んでからリネームする
+///
+/// 書き込み中に中断されても、書きかけのファイルが残らないようにする
+pub fn write_atomic(path: &Path, content: &str) -> io::Result<()> {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let tmp_path = path.with_file_name(format!(".{}.tmp-{}", file_name, std::process::id()));
+
+    let result = (|| {
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// `path`を`<ファイル名>.bak`に退避して退避先を返す
+///
+/// 以前の退避ファイルは上書きせず、`.bak.1`、`.bak.2`…と空いている名前を使う
+pub fn backup_file(path: &Path) -> io::Result<PathBuf> {
+    let content = fs::read(path)?;
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut index = 0;
+    loop {
+        let backup = match index {
+            0 => path.with_file_name(format!("{}.bak", file_name)),
+            n => path.with_file_name(format!("{}.bak.{}", file_name, n)),
+        };
+        match OpenOptions::new().write(true).create_new(true).open(&backup) {
+            Ok(mut file) => {
+                file.write_all(&content)?;
+                return Ok(backup);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => index += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::CcmgenError;
+
+/// `git diff`で検出された変更ファイル
+#[derive(Debug, Clone)]
+pub struct ChangedFile {
+    pub path: PathBuf,
+    pub added: bool,
+}
+
+/// プロジェクトが属するgitリポジトリの状態
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitInfo {
+    /// 現在のブランチ（detached HEADの場合はNone）
+    pub branch: Option<String>,
+    pub remote_url: Option<String>,
+    /// 未コミットの変更があるか
+    pub dirty: bool,
+    /// 直近のコミットの件名
+    pub last_commit: Option<String>,
+}
+
+/// リポジトリの状態を取得（gitコマンドは使わずgit2で読む）。gitリポジトリ外ではNone
+pub fn repository_info(path: &Path) -> Option<GitInfo> {
+    let repo = git2::Repository::discover(path).ok()?;
+    let head = repo.head().ok();
+
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(true);
+    let dirty = repo.statuses(Some(&mut options)).is_ok_and(|statuses| !statuses.is_empty());
+
+    Some(GitInfo {
+        branch: head.as_ref().filter(|h| h.is_branch()).and_then(|h| h.shorthand()).map(str::to_string),
+        remote_url: repo
+            .find_remote("origin")
+            .ok()
+            .and_then(|remote| remote.url().map(redact_credentials)),
+        dirty,
+        last_commit: head
+            .and_then(|h| h.peel_to_commit().ok())
+            .and_then(|commit| commit.summary().map(str::to_string)),
+    })
+}
+
+/// `https://token@host/...`のようなURLから認証情報を取り除く
+fn redact_credentials(url: &str) -> String {
+    match url.split_once("://") {
+        Some((scheme, rest)) => {
+            let authority_end = rest.find('/').unwrap_or(rest.len());
+            match rest[..authority_end].rfind('@') {
+                Some(at) => format!("{}://{}", scheme, &rest[at + 1..]),
+                None => url.to_string(),
+            }
+        }
+        None => url.to_string(),
+    }
+}
+
+/// `path`を含むリポジトリのルートディレクトリを取得
+pub fn repository_root(path: &Path) -> Result<PathBuf, CcmgenError> {
+    let output = run_git(path, &["rev-parse", "--show-toplevel"])?;
+    Ok(PathBuf::from(output.trim()))
+}
+
+/// 指定したrefから変更されたファイル一覧を取得（パスはリポジトリルートからの絶対パス）
+pub fn changed_files_since(path: &Path, git_ref: &str) -> Result<Vec<ChangedFile>, CcmgenError> {
+    let root = repository_root(path)?;
+    let output = run_git(path, &["diff", "--name-status", git_ref])?;
+
+    let files = output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split('\t');
+            let status = parts.next()?;
+            // リネーム・コピーは移動先のパスを採用
+            let file = parts.next_back()?;
+            Some(ChangedFile {
+                path: root.join(file),
+                added: status.starts_with('A'),
+            })
+        })
+        .collect();
+
+    Ok(files)
+}
+
+/// リポジトリを`dest`にクローン
+pub fn clone(url: &str, dest: &Path) -> Result<(), CcmgenError> {
+    let parent = dest.parent().unwrap_or(Path::new("."));
+    let dest = dest.to_string_lossy();
+    run_git(parent, &["clone", "--quiet", url, &dest]).map_err(|e| network_error(e, url))?;
+    Ok(())
+}
+
+/// 全ての変更をステージしてコミット。変更が無ければ`false`を返す
+pub fn commit_all(repo: &Path, message: &str) -> Result<bool, CcmgenError> {
+    run_git(repo, &["add", "-A"])?;
+    if run_git(repo, &["status", "--porcelain"])?.trim().is_empty() {
+        return Ok(false);
+    }
+    run_git(repo, &["commit", "--quiet", "-m", message])?;
+    Ok(true)
+}
+
+/// 現在のブランチをoriginにプッシュ
+pub fn push(repo: &Path, force: bool) -> Result<(), CcmgenError> {
+    let mut args = vec!["push", "--quiet", "origin", "HEAD"];
+    if force {
+        args.push("--force");
+    }
+    run_git(repo, &args).map_err(|e| network_error(e, "origin"))?;
+    Ok(())
+}
+
+/// プッシュがリモートの先行する履歴によって拒否された（non-fast-forward）か
+pub fn is_non_fast_forward(error: &CcmgenError) -> bool {
+    match error {
+        CcmgenError::NetworkFetch { message, .. } | CcmgenError::Git { message, .. } => {
+            message.contains("non-fast-forward") || message.contains("(fetch first)")
+        }
+        _ => false,
+    }
+}
+
+fn run_git(path: &Path, args: &[&str]) -> Result<String, CcmgenError> {
+    let output = Command::new("git").arg("-C").arg(path).args(args).output()?;
+    if !output.status.success() {
+        return Err(CcmgenError::Git {
+            command: args.first().copied().unwrap_or_default().to_string(),
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// リモートとの通信を伴うgitコマンドの失敗を`NetworkFetch`として扱う（gitを起動できない場合はそのまま）
+fn network_error(error: CcmgenError, url: &str) -> CcmgenError {
+    match error {
+        CcmgenError::Git { message, .. } => CcmgenError::NetworkFetch { url: url.to_string(), message },
+        other => other,
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::OnceLock;
+
+/// メッセージの表示言語
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Locale {
+    Ja,
+    En,
+}
+
+impl Locale {
+    /// `ja`、`en_US.UTF-8`などから表示言語を判定（英語以外は日本語）
+    pub fn parse(value: &str) -> Option<Locale> {
+        let value = value.trim().to_lowercase();
+        if value.starts_with("en") {
+            Some(Locale::En)
+        } else if value.starts_with("ja") {
+            Some(Locale::Ja)
+        } else {
+            None
+        }
+    }
+}
+
+/// `--locale`で指定された表示言語
+static ACTIVE_LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// 表示言語を設定（`CCMGEN_LANG`・`LANG`より優先）
+pub fn set_locale(locale: Locale) {
+    let _ = ACTIVE_LOCALE.set(locale);
+}
+
+/// 優先順位: `--locale` > `CCMGEN_LANG` > `LANG` > 日本語
+pub fn locale() -> Locale {
+    if let Some(locale) = ACTIVE_LOCALE.get() {
+        return *locale;
+    }
+    ["CCMGEN_LANG", "LANG"]
+        .iter()
+        .filter_map(|key| std::env::var(key).ok())
+        .find_map(|value| Locale::parse(&value))
+        .unwrap_or(Locale::Ja)
+}
+
+/// メッセージカタログ（キー、日本語、英語）。`{}`は引数で順番に置き換える
+const CATALOG: &[(&str, &str, &str)] = &[
+    ("detect.title", "🔍 プロジェクト検出結果:", "🔍 Detected project:"),
+    ("detect.name", "  名前: {}", "  Name: {}"),
+    ("detect.description", "  説明: {}", "  Description: {}"),
+    ("detect.type", "  種別: {}", "  Type: {}"),
+    ("detect.framework", "  フレームワーク: {}", "  Framework: {}"),
+    ("detect.bundler", "  バンドラー: {}", "  Bundler: {}"),
+    ("detect.path", "  パス: {}", "  Path: {}"),
+    ("detect.features", "  機能: {}", "  Features: {}"),
+    ("detect.evidence", "\n🧭 判定の根拠:", "\n🧭 Detection evidence:"),
+    ("detect.not_found", "❓ 対応するプロジェクトタイプが見つかりませんでした", "❓ No supported project type was found"),
+    ("analyze.basic_info", "📋 基本情報:", "📋 Basic information:"),
+    ("init.detected", "🔍 プロジェクトを検出しました: {} ({})", "🔍 Detected project: {} ({})"),
+    ("init.created", "✅ {} を作成しました", "✅ Created {}"),
+    ("init.updated", "🔄 {} を更新しました", "🔄 Updated {}"),
+    ("init.unchanged", "⏸️ {} は変更ありません (unchanged)", "⏸️ {} is unchanged"),
+    ("init.failed", "❌ {} の作成に失敗しました: {}", "❌ Failed to write {}: {}"),
+    (
+        "init.summary",
+        "📊 作成 {}個, 更新 {}個, 変更なし {}個, 失敗 {}個",
+        "📊 {} created, {} updated, {} unchanged, {} failed",
+    ),
+    (
+        "init.done",
+        "🎉 完了しました: ~/.claude/commands にコマンドが作成されました",
+        "🎉 Done: commands were written to ~/.claude/commands",
+    ),
+    (
+        "init.partial",
+        "⚠️ 一部のコマンドの作成に失敗しました: ~/.claude/commands の内容が不完全な可能性があります",
+        "⚠️ Some commands could not be written: ~/.claude/commands may be incomplete",
+    ),
+    ("init.template_not_found", "❌ テンプレートが見つかりません: {}", "❌ Template not found: {}"),
+    ("list.empty", "⚠️ ユーザーコマンドはまだ存在しません", "⚠️ No user commands exist yet"),
+    ("list.title", "📋 現在のユーザーコマンド一覧:", "📋 User commands:"),
+    ("remove.done", "🗑️ 削除しました: {}", "🗑️ Removed {}"),
+    ("remove.dry_run", "🔍 削除対象（--dry-run のため削除しません）: {}", "🔍 Would remove (dry run): {}"),
+    ("remove.not_found", "❓ 指定されたコマンドが見つかりません: {}", "❓ Command not found: {}"),
+];
+
+/// キーに対応する現在の表示言語のメッセージ（未登録のキーはそのまま返す）
+pub fn message(key: &'static str) -> &'static str {
+    CATALOG
+        .iter()
+        .find(|(k, _, _)| *k == key)
+        .map(|(_, ja, en)| match locale() {
+            Locale::Ja => *ja,
+            Locale::En => *en,
+        })
+        .unwrap_or(key)
+}
+
+/// メッセージ中の`{}`を引数で順番に置き換える
+pub fn format_message(template: &str, args: &[String]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(pos) = rest.find("{}") {
+        result.push_str(&rest[..pos]);
+        result.push_str(args.next().map(String::as_str).unwrap_or("{}"));
+        rest = &rest[pos + 2..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// 現在の表示言語でメッセージを返す。`t!("init.created", name)`
+#[macro_export]
+macro_rules! t {
+    ($key:expr) => {
+        $crate::i18n::message($key).to_string()
+    };
+    ($key:expr, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::format_message($crate::i18n::message($key), &[$($arg.to_string()),+])
+    };
+}
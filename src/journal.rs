@@ -0,0 +1,83 @@
+use std::fs;
+use std::io;
+use std::path::{self, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::claude_dir;
+use crate::error::CcmgenError;
+
+/// 上書きされたファイルと元の内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverwrittenFile {
+    pub path: PathBuf,
+    pub previous_content: String,
+    /// `init --backup-existing`で上書き前に退避したファイル
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup: Option<PathBuf>,
+}
+
+/// 直近の`init`で変更されたファイルの記録
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Journal {
+    pub created: Vec<PathBuf>,
+    pub overwritten: Vec<OverwrittenFile>,
+}
+
+impl Journal {
+    pub fn get_journal_path() -> Result<PathBuf, CcmgenError> {
+        Ok(claude_dir()?.join(".ccmgen-journal.json"))
+    }
+
+    pub fn record_created(&mut self, path: PathBuf) {
+        self.created.push(absolute(path));
+    }
+
+    pub fn record_overwritten(&mut self, path: PathBuf, previous_content: String, backup: Option<PathBuf>) {
+        self.overwritten.push(OverwrittenFile { path: absolute(path), previous_content, backup: backup.map(absolute) });
+    }
+
+    /// 別のジャーナルの記録を追加（`init --recursive --parallel`でプロジェクト毎の記録をまとめる）
+    pub fn extend(&mut self, other: Journal) {
+        self.created.extend(other.created);
+        self.overwritten.extend(other.overwritten);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.overwritten.is_empty()
+    }
+
+    /// ジャーナルを保存（直近の操作のみ保持するため常に上書き）
+    pub fn save(&self) -> Result<(), CcmgenError> {
+        let path = Self::get_journal_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// 直近の`init`の記録として保存。何も変更しなかった場合は以前の記録を消す（`undo`が古い操作を取り消さないように）
+    pub fn save_or_clear(&self) -> Result<(), CcmgenError> {
+        if self.is_empty() { Self::clear() } else { self.save() }
+    }
+
+    pub fn load() -> Option<Self> {
+        let content = fs::read_to_string(Self::get_journal_path().ok()?).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn clear() -> Result<(), CcmgenError> {
+        let path = Self::get_journal_path()?;
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+/// 別のディレクトリから`undo`しても同じファイルを指すよう絶対パスにする（`init --recursive repos`などの相対パス対策）
+fn absolute(path: PathBuf) -> PathBuf {
+    path::absolute(&path).unwrap_or(path)
+}
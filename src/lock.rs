@@ -0,0 +1,93 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config::claude_dir;
+use crate::error::CcmgenError;
+
+/// ロック取得を待つ最大時間
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+const RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// `~/.claude`を変更する操作の間保持するアドバイザリロック
+///
+/// ロックファイルの排他的な作成で取得し、ドロップ時に削除する。記録したPIDのプロセスが終了していれば引き継ぐ
+pub struct CommandLock {
+    path: PathBuf,
+}
+
+impl CommandLock {
+    pub fn get_lock_path() -> Result<PathBuf, CcmgenError> {
+        Ok(claude_dir()?.join(".ccmgen.lock"))
+    }
+
+    pub fn acquire() -> Result<Self, CcmgenError> {
+        let path = Self::get_lock_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let started = Instant::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    writeln!(file, "{}", std::process::id())?;
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    // 強制終了などで残ったロックは削除して取り直す（作成は create_new なので取得できるのは1プロセスだけ）
+                    if let Some(pid) = stale_owner(&path) {
+                        eprintln!("🔓 終了したプロセス (PID {}) のロックを引き継ぎます: {}", pid, path.display());
+                        match fs::remove_file(&path) {
+                            Ok(()) => continue,
+                            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                            Err(e) => return Err(e.into()),
+                        }
+                    }
+                    if started.elapsed() >= LOCK_TIMEOUT {
+                        return Err(CcmgenError::LockTimeout(path));
+                    }
+                    thread::sleep(RETRY_INTERVAL);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+/// ロックファイルに記録されたPIDのプロセスが終了していればそのPID（書き込み途中などで読めない場合は実行中とみなす）
+fn stale_owner(path: &Path) -> Option<u32> {
+    let pid: u32 = fs::read_to_string(path).ok()?.trim().parse().ok()?;
+    (!process_running(pid)).then_some(pid)
+}
+
+/// プロセスが実行中か。確認できない場合は実行中とみなす
+fn process_running(pid: u32) -> bool {
+    if pid == std::process::id() {
+        return true;
+    }
+
+    #[cfg(windows)]
+    let output = Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/NH", "/FO", "CSV"])
+        .stderr(Stdio::null())
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).contains(&format!("\"{}\"", pid)));
+    #[cfg(not(windows))]
+    let output = Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success());
+
+    output.unwrap_or(true)
+}
+
+impl Drop for CommandLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
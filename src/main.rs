@@ -2,6 +2,13 @@ use clap::{Parser, Subcommand};
 
 mod commands;
 mod config;
+mod docs;
+mod error;
+mod fsutil;
+mod git;
+mod i18n;
+mod journal;
+mod lock;
 mod project;
 mod smart_templates;
 mod templates;
@@ -9,10 +16,32 @@ mod templates;
 #[derive(Parser)]
 #[command(about = "Claude Code User Command Initializer", long_about = None)]
 struct Cli {
+    /// 使用する設定プロファイル（環境変数 CCMGEN_PROFILE でも指定可）
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// 表示言語（ja または en。環境変数 CCMGEN_LANG・LANG でも指定可）
+    #[arg(long, global = true)]
+    locale: Option<String>,
+
+    /// プロジェクトを検出できない場合（removeは削除するコマンドが無い場合）に終了コード1で終了（detect・init・analyze・remove。CIでの確認用）
+    #[arg(long, global = true)]
+    strict: bool,
+
+    /// 確認・選択を表示せず既定値で進める（標準入力が端末でない場合も同様。既定値の無い選択はエラー）
+    #[arg(short, long, global = true, visible_alias = "no-input")]
+    yes: bool,
+
+    /// コマンドファイルの拡張子（既定は md、"" で拡張子なし。設定ファイルの commands_ext より優先）
+    #[arg(long, global = true, value_name = "EXT")]
+    commands_ext: Option<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
 
+// Init は引数が多いが、一度だけ構築されるのでBox化しない
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
     /// 言語毎のセットアップ
@@ -25,6 +54,12 @@ enum Commands {
     /// - JavaScript
     /// - TypeScript  
     /// - Node.js
+    /// - Bun
+    /// - Unity
+    /// - R
+    /// - Haskell
+    /// - Scala
+    /// - Python
     Init {
         #[arg(short, long)]
         lang: Option<String>,
@@ -32,51 +67,392 @@ enum Commands {
         repo: Option<String>,
         #[arg(short, long)]
         path: Option<String>,
+        /// テンプレート毎に採用・スキップ・エディタで編集を選択
+        #[arg(long)]
+        interactive: bool,
+        /// 指定したテンプレートのみを生成
+        #[arg(long)]
+        template: Option<String>,
+        /// 自動検出せず設定ファイルの [init] templates を順番通りに生成
+        #[arg(long, conflicts_with_all = ["lang", "template"])]
+        from_config: bool,
+        /// --template で指定したコマンドにテキストを追記（無ければ作成）
+        #[arg(long, value_name = "TEXT", requires = "template", conflicts_with_all = ["interactive", "from_config"])]
+        append: Option<String>,
+        /// 直下のサブディレクトリのプロジェクト毎に <project>/.claude/commands を生成
+        #[arg(
+            long,
+            value_name = "DIR",
+            group = "roots",
+            conflicts_with_all = ["path", "lang", "template", "interactive", "from_config", "max_templates", "preset", "only_suggested"]
+        )]
+        recursive: Option<String>,
+        /// パターンに一致したディレクトリ（例: 'services/*'）をそれぞれプロジェクトとして <project>/.claude/commands を生成
+        #[arg(
+            long,
+            value_name = "PATTERN",
+            group = "roots",
+            conflicts_with_all = ["path", "lang", "template", "interactive", "from_config", "max_templates", "preset", "only_suggested"]
+        )]
+        glob: Option<String>,
+        /// --recursive・--glob で書き込まずに処理予定のみ表示
+        #[arg(long, requires = "roots")]
+        dry_run: bool,
+        /// --recursive・--glob の各プロジェクトを並列に処理（出力はプロジェクト毎にまとめて表示）
+        #[arg(long, requires = "roots")]
+        parallel: bool,
+        /// --parallel の同時実行数（既定はCPU数）
+        #[arg(long, value_name = "N", requires = "parallel")]
+        jobs: Option<usize>,
+        /// 生成するファイル名のプレフィックス（{lang}、{project} を展開。例: "{lang}" → rust-generate-tests.md）
+        #[arg(long, value_name = "PREFIX")]
+        name_prefix: Option<String>,
+        /// 生成するテンプレート数の上限（プロジェクト固有の提案を優先）
+        #[arg(long, value_name = "N", conflicts_with = "template")]
+        max_templates: Option<usize>,
+        /// まとめて生成するプリセット（minimal・testing・docs・full、設定ファイルの [presets] で追加可）
+        #[arg(long, value_name = "NAME", conflicts_with_all = ["template", "from_config"])]
+        preset: Option<String>,
+        /// 検出したプロジェクトの推奨コマンド（ccmgen analyze の「推奨プロジェクト固有コマンド」）のみを生成
+        #[arg(long, conflicts_with_all = ["lang", "template", "from_config", "preset", "no_context"])]
+        only_suggested: bool,
+        /// プロジェクト情報の付加・テンプレート変数の展開をせず、組み込みテンプレートの本文をそのまま生成
+        #[arg(long, conflicts_with = "append")]
+        no_context: bool,
+        /// 上書きする既存のコマンドを <name>.md.bak（既にあれば .bak.1 など）に退避してから書き込む（ccmgen undo で退避先を表示）
+        #[arg(long, conflicts_with = "dry_run")]
+        backup_existing: bool,
+        /// テンプレート本文の {{key}} に埋め込む変数（例: --template-vars ticket=JIRA-123 reviewer=alice）
+        #[arg(long, value_name = "KEY=VALUE", num_args = 1.., value_parser = parse_template_var, conflicts_with = "no_context")]
+        template_vars: Vec<(String, String)>,
+        /// 書き込む前の確認を省略（グローバルな --yes と同じ）
+        #[arg(long)]
+        force: bool,
+        /// 生成後に設定ファイルの post_init_hook を実行
+        #[arg(long, overrides_with = "no_hooks")]
+        run_hooks: bool,
+        /// post_init_hook を実行しない（デフォルト）
+        #[arg(long, overrides_with = "run_hooks")]
+        no_hooks: bool,
     },
     /// プロジェクト情報を表示
     Detect {
         #[arg(short, long)]
         path: Option<String>,
+        /// パターンに一致したディレクトリ（例: 'services/*'）毎に検出してまとめて表示（--json は配列）
+        #[arg(long, value_name = "PATTERN", conflicts_with = "path")]
+        glob: Option<String>,
+        /// 判定の根拠となったファイル・マーカーを表示
+        #[arg(long)]
+        explain: bool,
+        /// 検出結果をJSONで出力（検出できなければ {"error": "project_not_found", ...}）
+        #[arg(long, conflicts_with = "explain")]
+        json: bool,
     },
     /// 作成済みコマンドを一覧表示
-    List,
+    List {
+        /// 表示するページ番号（1始まり）
+        #[arg(long)]
+        page: Option<usize>,
+        /// 1ページあたりの件数
+        #[arg(long, default_value_t = 50)]
+        per_page: usize,
+        /// 件数が多い場合でもページャを使わない
+        #[arg(long)]
+        no_pager: bool,
+        /// 指定したプレフィックスで始まるコマンドのみ表示
+        #[arg(long)]
+        prefix: Option<String>,
+        /// 名前・パス・サイズ・更新日時・先頭行をJSONで出力（--output-format json と同じ）
+        #[arg(long, conflicts_with_all = ["page", "no_pager", "output_format"])]
+        json: bool,
+        /// 出力形式（plain: 1行1件、json: 構造化、null: NUL区切り）
+        #[arg(long, value_enum, default_value_t = commands::OutputFormat::Human)]
+        output_format: commands::OutputFormat,
+        /// テンプレートの作成者・バージョン（フロントマターの author・version）も表示
+        #[arg(short, long)]
+        verbose: bool,
+        /// 名前を - で区切り、共通のプレフィックス毎にまとめてツリー表示（wasm-optimize・wasm-types → wasm- の下）
+        #[arg(long, conflicts_with_all = ["page", "json", "output_format"])]
+        tree: bool,
+    },
     /// 指定したコマンドを削除
     Remove {
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        name: Option<String>,
+        /// コマンドディレクトリ内のコマンドファイル（--commands-ext の拡張子）をすべて削除
+        #[arg(long)]
+        all: bool,
+        /// --all の確認をスキップ
+        #[arg(long, requires = "all")]
+        force: bool,
+        /// 削除対象のファイルを表示するだけで削除しない
+        #[arg(long)]
+        dry_run: bool,
+        /// 出力形式（plain: 削除したコマンド名を1行1件、json: 構造化、null: NUL区切り）
+        #[arg(long, value_enum, default_value_t = commands::OutputFormat::Human)]
+        output_format: commands::OutputFormat,
+    },
+    /// コマンドをgitリポジトリの .claude/commands にコピーしてコミット・プッシュ
+    Sync {
+        repo: String,
+        /// コミットメッセージ
+        #[arg(short, long)]
+        message: Option<String>,
+    },
+    /// 直近の init で作成・上書きしたファイルを元に戻す
+    Undo,
+    /// コマンドファイルのパスを表示
+    Which {
         name: String,
     },
+    /// コマンドの内容を表示
+    Show {
+        name: String,
+        /// 表示する代わりにクリップボードにコピー（使えない環境では表示。Linuxでは他の内容がコピーされるまでバックグラウンドで保持）
+        #[arg(long)]
+        copy: bool,
+        /// 内容の代わりに作成者・バージョン（フロントマターの author・version）を表示
+        #[arg(long, conflicts_with = "copy")]
+        meta: bool,
+    },
+    /// show --copy が起動する補助プロセス（標準入力の内容をクリップボードに保持する）
+    #[command(name = "__hold-clipboard", hide = true)]
+    HoldClipboard,
     /// 設定ファイルを初期化
-    Config,
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+    },
+    /// 設定ファイルのJSON Schemaを出力
+    Schema,
+    /// 組み込みテンプレートを操作
+    Templates {
+        /// 指定した言語の組み込みテンプレートをディレクトリに .md ファイルとして書き出す
+        #[arg(long, num_args = 2, value_names = ["LANG", "DIR"], required = true)]
+        export: Vec<String>,
+    },
     /// プロジェクト詳細分析と推奨コマンド表示
     Analyze {
         #[arg(short, long)]
         path: Option<String>,
+        /// パターンに一致したディレクトリ（例: 'services/*'）毎に分析してまとめて表示（--json は配列）
+        #[arg(long, value_name = "PATTERN", conflicts_with_all = ["path", "watch"])]
+        glob: Option<String>,
+        /// 指定したgit refからの変更ファイルのみを分析
+        #[arg(long)]
+        since: Option<String>,
+        /// Markdownのリンク切れとREADMEの標準セクションをチェック
+        #[arg(long)]
+        check_docs: bool,
+        /// 推奨コマンド名のみを1行ずつ出力（xargs ccmgen init --template などへのパイプ用）
+        #[arg(long, conflicts_with = "check_docs")]
+        suggest_only: bool,
+        /// スキャンしたディレクトリ・スキップの判断・メタデータ抽出の過程を標準エラーに表示
+        #[arg(short, long)]
+        verbose: bool,
+        /// 自動生成されたファイルもソース・テストファイルとして数える
+        #[arg(long)]
+        include_generated: bool,
+        /// ファイルの変更を監視し、変更毎に分析結果を再表示（target・node_modules等の変更は無視）
+        #[arg(long, conflicts_with = "suggest_only")]
+        watch: bool,
+        /// 分析結果をJSONで出力（検出できなければ {"error": "project_not_found", ...}）
+        #[arg(long, conflicts_with_all = ["suggest_only", "check_docs", "watch"])]
+        json: bool,
+        /// 表示する依存関係の件数（既定 10）
+        #[arg(long, value_name = "N")]
+        deps: Option<usize>,
+        /// 依存関係をすべて表示
+        #[arg(long, conflicts_with = "deps")]
+        all_deps: bool,
+        /// 依存関係の並び順（name: 名前順、count: 参照しているファイル数の多い順）
+        #[arg(long, value_enum, value_name = "ORDER", default_value_t = commands::DepsSort::Name)]
+        sort_deps: commands::DepsSort,
     },
 }
 
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// 古い設定ファイルを現在の形式に更新（不足項目をデフォルト値で補完し、元のファイルを .bak に退避。最新なら書き換えない）
+    Migrate,
+}
+
 fn main() {
     let cli = Cli::parse();
 
+    if let Some(profile) = &cli.profile {
+        config::ConfigManager::set_active_profile(profile.clone());
+    }
+
+    if let Some(locale) = &cli.locale {
+        match i18n::Locale::parse(locale) {
+            Some(locale) => i18n::set_locale(locale),
+            None => {
+                eprintln!("❌ 不明な表示言語です: {} (ja または en)", locale);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    if cli.yes {
+        commands::disable_input();
+    }
+    if let Some(ext) = &cli.commands_ext {
+        commands::set_commands_ext(ext);
+    }
+
     match &cli.command {
-        Some(Commands::Init { lang, repo, path }) => {
-            commands::init(lang.clone(), repo.clone(), path.clone());
+        Some(Commands::Init {
+            lang, repo, path, interactive, template, from_config, append, recursive, glob, dry_run, parallel, jobs,
+            name_prefix, max_templates, preset, only_suggested, no_context, template_vars, backup_existing, force, run_hooks, no_hooks,
+        }) => {
+            if *force {
+                commands::disable_input();
+            }
+            if *no_context {
+                smart_templates::disable_context();
+            }
+            smart_templates::set_template_vars(template_vars.clone());
+            if *backup_existing {
+                commands::enable_backups();
+            }
+            let lang = match lang.as_deref().map(|l| (l, commands::resolve_language(l))) {
+                Some((_, Some(resolved))) => Some(resolved.to_string()),
+                Some((input, None)) => {
+                    eprintln!("❌ 不明な言語です: {}", input);
+                    eprintln!("   利用可能な言語: {}", commands::LANGUAGES.join(", "));
+                    std::process::exit(2);
+                }
+                None => None,
+            };
+            let succeeded = commands::init(commands::InitOptions {
+                lang,
+                repo: repo.clone(),
+                path: path.clone(),
+                interactive: *interactive,
+                template: template.clone(),
+                run_hooks: *run_hooks && !*no_hooks,
+                from_config: *from_config,
+                max_templates: *max_templates,
+                append: append.clone(),
+                recursive: recursive.clone(),
+                glob: glob.clone(),
+                dry_run: *dry_run,
+                name_prefix: name_prefix.clone(),
+                preset: preset.clone(),
+                only_suggested: *only_suggested,
+                jobs: parallel.then(|| {
+                    jobs.unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4))
+                }),
+                strict: cli.strict,
+            });
+            if !succeeded {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Detect { path, glob, explain, json }) => {
+            if !commands::detect(path.clone(), glob.clone(), *explain, *json) && cli.strict {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::List { page, per_page, no_pager, prefix, json, output_format, verbose, tree }) => {
+            let format = if *json { commands::OutputFormat::Json } else { *output_format };
+            commands::list(*page, *per_page, *no_pager, format, prefix.as_deref(), *verbose, *tree);
+        }
+        Some(Commands::Remove { name, all, force, dry_run, output_format }) => {
+            let status = if *all {
+                commands::remove_all(*force, *dry_run, *output_format)
+            } else if let Some(name) = name {
+                commands::remove(name, *dry_run, *output_format)
+            } else {
+                commands::RemoveStatus::Done
+            };
+            // 削除の失敗は常に、コマンドが無いのは --strict のときだけ終了コード1
+            if status == commands::RemoveStatus::Failed || (status == commands::RemoveStatus::NotFound && cli.strict) {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Sync { repo, message }) => {
+            commands::sync(repo, message.clone());
+        }
+        Some(Commands::Undo) => {
+            commands::undo();
         }
-        Some(Commands::Detect { path }) => {
-            commands::detect(path.clone());
+        Some(Commands::Which { name }) => {
+            if !commands::which(name) {
+                std::process::exit(1);
+            }
         }
-        Some(Commands::List) => {
-            commands::list();
+        Some(Commands::Show { name, copy, meta }) => {
+            if !commands::show(name, *copy, *meta) {
+                std::process::exit(1);
+            }
         }
-        Some(Commands::Remove { name }) => {
-            commands::remove(name);
+        Some(Commands::HoldClipboard) => {
+            if !commands::hold_clipboard() {
+                std::process::exit(1);
+            }
         }
-        Some(Commands::Config) => {
+        Some(Commands::Config { action: None }) => {
             commands::config();
         }
-        Some(Commands::Analyze { path }) => {
-            commands::analyze(path.clone());
+        Some(Commands::Config { action: Some(ConfigAction::Migrate) }) => {
+            if !commands::config_migrate() {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Schema) => {
+            commands::schema();
+        }
+        Some(Commands::Templates { export }) => {
+            let (input, dir) = (&export[0], &export[1]);
+            let Some(lang) = commands::resolve_language(input) else {
+                eprintln!("❌ 不明な言語です: {}", input);
+                eprintln!("   利用可能な言語: {}", commands::LANGUAGES.join(", "));
+                std::process::exit(2);
+            };
+            if !commands::export_templates(lang, std::path::Path::new(dir)) {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Analyze {
+            path, glob, since, check_docs, suggest_only, verbose, include_generated, watch, json, deps, all_deps, sort_deps,
+        }) => {
+            let limit = if *all_deps { None } else { Some(deps.unwrap_or(commands::DEFAULT_DEPS_LIMIT)) };
+            commands::set_deps_view(limit, *sort_deps);
+            if *verbose {
+                project::enable_trace();
+            }
+            if *include_generated {
+                project::include_generated_files();
+            }
+            let output = if *json {
+                commands::AnalyzeOutput::Json
+            } else if *suggest_only {
+                commands::AnalyzeOutput::SuggestOnly
+            } else {
+                commands::AnalyzeOutput::Human
+            };
+            if !commands::analyze(path.clone(), glob.clone(), since.clone(), *check_docs, output, *watch) && cli.strict {
+                std::process::exit(1);
+            }
         }
         None => {
             println!("✨ Try: ccmgen init");
         }
     }
 }
+
+/// `--template-vars`の`key=value`を分解する。キーはテンプレートで参照できる識別子に限る
+fn parse_template_var(arg: &str) -> Result<(String, String), String> {
+    let (key, value) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("key=value の形式で指定してください: {}", arg))?;
+    let valid_key = key.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid_key {
+        return Err(format!("変数名は英字・数字・_ のみ使用できます（先頭は数字以外）: {}", key));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
@@ -1,8 +1,15 @@
 use std::path::{Path, PathBuf};
 use std::fs;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
 use serde::{Deserialize, Serialize};
 
+use crate::config::{ConfigManager, FileCategory, ProjectConfig, ProjectSizeConfig, PROJECT_CONFIG_FILE};
+use crate::docs;
+use crate::error::CcmgenError;
+use crate::git::{self, ChangedFile, GitInfo};
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ProjectType {
     RustNormal,
@@ -10,25 +17,310 @@ pub enum ProjectType {
     JavaScript,
     TypeScript,
     NodeJs,
+    Bun,
+    Unity,
+    R,
+    Haskell,
+    Scala,
+    Python,
+}
+
+impl ProjectType {
+    /// 設定ファイルの`custom_templates`で使う言語キー
+    pub fn language_key(&self) -> &'static str {
+        match self {
+            ProjectType::RustNormal | ProjectType::RustWasm => "rust",
+            ProjectType::JavaScript => "javascript",
+            ProjectType::TypeScript => "typescript",
+            ProjectType::NodeJs => "nodejs",
+            ProjectType::Bun => "bun",
+            ProjectType::Unity => "unity",
+            ProjectType::R => "r",
+            ProjectType::Haskell => "haskell",
+            ProjectType::Scala => "scala",
+            ProjectType::Python => "python",
+        }
+    }
+
+    /// 互いに細分化の関係にある種別のまとまり（TypeScriptはJavaScriptの一種として扱う）
+    fn family(&self) -> &'static str {
+        match self {
+            ProjectType::RustNormal | ProjectType::RustWasm => "rust",
+            ProjectType::JavaScript | ProjectType::TypeScript | ProjectType::NodeJs | ProjectType::Bun => "javascript",
+            other => other.language_key(),
+        }
+    }
+
+    /// 種別名（`RustWasm`など）または言語キー（`rust`など）から種別を返す
+    pub fn parse(value: &str) -> Option<ProjectType> {
+        match value {
+            "RustNormal" => Some(ProjectType::RustNormal),
+            "RustWasm" => Some(ProjectType::RustWasm),
+            "JavaScript" => Some(ProjectType::JavaScript),
+            "TypeScript" => Some(ProjectType::TypeScript),
+            "NodeJs" => Some(ProjectType::NodeJs),
+            "Bun" => Some(ProjectType::Bun),
+            "Unity" => Some(ProjectType::Unity),
+            "R" => Some(ProjectType::R),
+            "Haskell" => Some(ProjectType::Haskell),
+            "Scala" => Some(ProjectType::Scala),
+            "Python" => Some(ProjectType::Python),
+            other => Self::from_language_key(&other.to_lowercase()),
+        }
+    }
+
+    /// 言語キーから代表的なプロジェクト種別を返す
+    pub fn from_language_key(key: &str) -> Option<ProjectType> {
+        match key {
+            "rust" => Some(ProjectType::RustNormal),
+            "rust-wasm" => Some(ProjectType::RustWasm),
+            "javascript" => Some(ProjectType::JavaScript),
+            "typescript" => Some(ProjectType::TypeScript),
+            "nodejs" => Some(ProjectType::NodeJs),
+            "bun" => Some(ProjectType::Bun),
+            "unity" => Some(ProjectType::Unity),
+            "r" => Some(ProjectType::R),
+            "haskell" => Some(ProjectType::Haskell),
+            "scala" => Some(ProjectType::Scala),
+            "python" => Some(ProjectType::Python),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MetaFramework {
+    Next,
+    Nuxt,
+    Astro,
+    SvelteKit,
+    Remix,
+    Qwik,
+}
+
+impl MetaFramework {
+    /// ファイルベースルーティングのディレクトリと`features`に記録する識別子
+    fn routing_convention(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            MetaFramework::SvelteKit => Some(("src/routes", "sveltekit")),
+            MetaFramework::Remix => Some(("app/routes", "remix")),
+            MetaFramework::Qwik => Some(("src/routes", "qwik-city")),
+            MetaFramework::Next | MetaFramework::Nuxt | MetaFramework::Astro => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Bundler {
+    Vite,
+    Webpack,
+    Rollup,
+    Esbuild,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectInfo {
     pub project_type: ProjectType,
     pub name: String,
+    /// マニフェストに記載されたプロジェクトの説明
+    #[serde(default)]
+    pub description: Option<String>,
     pub path: PathBuf,
     pub features: Vec<String>,
+    #[serde(default)]
+    pub meta_framework: Option<MetaFramework>,
+    #[serde(default)]
+    pub bundler: Option<Bundler>,
+    /// Cargo.tomlの`package.edition`（Rustのみ）
+    #[serde(default)]
+    pub rust_edition: Option<String>,
+    /// Cargo.tomlの`package.rust-version`（MSRV、Rustのみ）
+    #[serde(default)]
+    pub rust_version: Option<String>,
+    /// 種別判定の根拠となったマーカー
+    #[serde(default)]
+    pub evidence: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectStructure {
     pub source_files: Vec<PathBuf>,
     pub test_files: Vec<PathBuf>,
+    /// `#[cfg(test)]`のインラインテストを含むソースファイル
+    #[serde(default)]
+    pub inline_test_files: Vec<PathBuf>,
+    /// Rustの`examples/`以下のサンプル（ソースファイルにも含む）
+    #[serde(default)]
+    pub example_files: Vec<PathBuf>,
+    /// Rustの`benches/`以下のベンチマーク（ソースファイルにも含む）
+    #[serde(default)]
+    pub bench_files: Vec<PathBuf>,
     pub config_files: Vec<PathBuf>,
     pub doc_files: Vec<PathBuf>,
     pub dependencies: HashMap<String, String>,
     pub scripts: HashMap<String, String>,
     pub entry_points: Vec<PathBuf>,
+    pub schema_files: Vec<PathBuf>,
+    pub file_mtimes: HashMap<PathBuf, SystemTime>,
+    /// 拡張子毎のソース・テストコードの行数
+    pub loc_by_extension: HashMap<String, usize>,
+    /// プロジェクト直下に存在する主要ディレクトリ（`.github`、`docs`など）
+    pub directories: HashSet<String>,
+    /// 自動生成されたコード（`--include-generated`指定時以外はソース・テストに含めない）
+    #[serde(default)]
+    pub generated_files: Vec<PathBuf>,
+    /// プロジェクト直下のREADME（`README.md`/`.rst`/`.txt`）
+    #[serde(default)]
+    pub readme: Option<PathBuf>,
+    /// プロジェクト直下のライセンスファイル（`LICENSE`、`LICENSE-MIT`、`COPYING`など）
+    #[serde(default)]
+    pub license_files: Vec<PathBuf>,
+    /// READMEから抽出したキーワード（重みの大きい順）
+    #[serde(default)]
+    pub readme_topics: Vec<String>,
+    /// `.ccmgen.toml`の`ignore_dirs`（スキャン対象外）
+    #[serde(skip)]
+    ignore_dirs: Vec<String>,
+    /// 設定ファイルの`file_categories`（拡張子 → 分類）
+    #[serde(skip)]
+    file_categories: HashMap<String, FileCategory>,
+    /// Unityプロジェクトか（`Library`・`Temp`などを生成物として除外する）
+    #[serde(skip)]
+    unity: bool,
+    /// スキャン時に検出したコンテナ関連の設定（`container_setup`）
+    #[serde(skip)]
+    container_setup: ContainerSetup,
+}
+
+/// ソース・テストファイル数によるプロジェクトの規模
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProjectSize {
+    Small,
+    Medium,
+    Large,
+}
+
+/// テストファイルの置き場所（`ProjectStructure::test_locations`）
+#[derive(Debug, Clone)]
+pub struct TestLocation {
+    /// プロジェクトルートからの相対パス
+    pub directory: PathBuf,
+    /// `tests/`・`__tests__/`などテスト用のディレクトリか（falseならソースと同じ場所）
+    pub dedicated: bool,
+    pub count: usize,
+    /// 代表的なテストファイル（ルートからの相対パス）
+    pub example: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CiSystem {
+    GitHubActions,
+    GitLabCi,
+    CircleCi,
+    AzurePipelines,
+    Jenkins,
+}
+
+impl CiSystem {
+    /// `features`に記録する識別子
+    pub fn key(&self) -> &'static str {
+        match self {
+            CiSystem::GitHubActions => "github-actions",
+            CiSystem::GitLabCi => "gitlab-ci",
+            CiSystem::CircleCi => "circleci",
+            CiSystem::AzurePipelines => "azure-pipelines",
+            CiSystem::Jenkins => "jenkins",
+        }
+    }
+}
+
+/// コンテナ関連の設定ファイル（`ProjectStructure::container_setup`）
+#[derive(Debug, Clone, Default)]
+pub struct ContainerSetup {
+    /// `Dockerfile`・`Containerfile`・`Dockerfile.*`・`*.Dockerfile`
+    pub dockerfiles: Vec<PathBuf>,
+    /// `FROM`が複数あるマルチステージビルドのDockerfile
+    pub multi_stage: Vec<PathBuf>,
+    /// `docker-compose.yml`・`compose.yaml`など
+    pub compose_files: Vec<PathBuf>,
+    /// `.devcontainer/devcontainer.json`・`.devcontainer.json`
+    pub devcontainer: Option<PathBuf>,
+    /// `apiVersion:`と`kind:`を持つYAML、`kustomization.yaml`、Helmの`Chart.yaml`
+    pub k8s_manifests: Vec<PathBuf>,
+}
+
+impl ContainerSetup {
+    /// `features`に記録する識別子
+    pub fn features(&self) -> Vec<&'static str> {
+        let mut features = Vec::new();
+        if !self.compose_files.is_empty() {
+            features.push("compose");
+        }
+        if self.devcontainer.is_some() {
+            features.push("devcontainer");
+        }
+        if !self.k8s_manifests.is_empty() {
+            features.push("k8s");
+        }
+        features
+    }
+}
+
+/// ドキュメントサイトジェネレータ
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DocsSite {
+    MdBook,
+    Docusaurus,
+    VitePress,
+}
+
+/// リンター・フォーマッター
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LintTool {
+    Rustfmt,
+    Clippy,
+    Eslint,
+    Prettier,
+    Biome,
+    Ruff,
+    EditorConfig,
+}
+
+impl LintTool {
+    /// `features`に記録する識別子
+    pub fn key(&self) -> &'static str {
+        match self {
+            LintTool::Rustfmt => "rustfmt",
+            LintTool::Clippy => "clippy",
+            LintTool::Eslint => "eslint",
+            LintTool::Prettier => "prettier",
+            LintTool::Biome => "biome",
+            LintTool::Ruff => "ruff",
+            LintTool::EditorConfig => "editorconfig",
+        }
+    }
+
+    /// 設定ファイル名から判定
+    fn from_file_name(name: &str) -> Option<LintTool> {
+        match name {
+            "rustfmt.toml" | ".rustfmt.toml" => Some(LintTool::Rustfmt),
+            "clippy.toml" | ".clippy.toml" => Some(LintTool::Clippy),
+            "biome.json" | "biome.jsonc" => Some(LintTool::Biome),
+            "ruff.toml" | ".ruff.toml" => Some(LintTool::Ruff),
+            ".editorconfig" => Some(LintTool::EditorConfig),
+            name if name.starts_with(".eslintrc") || name.starts_with("eslint.config.") => Some(LintTool::Eslint),
+            name if name.starts_with(".prettierrc") || name.starts_with("prettier.config.") => Some(LintTool::Prettier),
+            _ => None,
+        }
+    }
+}
+
+/// OpenAPI/Swagger仕様ファイルの概要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiSpec {
+    pub path: PathBuf,
+    pub title: Option<String>,
+    pub version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,60 +328,537 @@ pub struct ProjectContext {
     pub info: ProjectInfo,
     pub structure: ProjectStructure,
     pub suggested_commands: Vec<String>,
+    /// gitリポジトリ外ではNone
+    #[serde(default)]
+    pub git: Option<GitInfo>,
+    /// プロジェクト直下の`.ccmgen.toml`（コンテキスト作成時に1回だけ読み込む）
+    #[serde(skip)]
+    pub project_config: Option<ProjectConfig>,
+}
+
+/// サブディレクトリにRustのバックエンドとJS/TSのフロントエンドを持つフルスタック構成
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FullStackLayout {
+    pub backend: ProjectContext,
+    pub frontend: ProjectContext,
+}
+
+impl FullStackLayout {
+    /// (領域名, コンテキスト)。生成するコマンド名のプレフィックス（`backend-`など）に使う
+    pub fn areas(&self) -> [(&'static str, &ProjectContext); 2] {
+        [("backend", &self.backend), ("frontend", &self.frontend)]
+    }
+}
+
+/// バックエンド・フロントエンドとして調べるディレクトリ名
+const BACKEND_DIRS: &[&str] = &["backend", "server", "api"];
+const FRONTEND_DIRS: &[&str] = &["frontend", "web", "client", "app", "ui"];
+
+/// プロジェクト種別の候補と確信度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectionCandidate {
+    pub project_type: ProjectType,
+    /// 一致したマーカーの重み付き合計
+    pub score: u32,
+    /// 別系統の候補のスコアと比べた割合 (0.0〜1.0)。同系統の候補（TypeScriptとNodeJsなど）は競合とみなさない
+    pub confidence: f32,
 }
 
 pub struct ProjectDetector;
 
+/// プロジェクト種別の検出器（`DETECTORS`に優先順で登録する）
+pub struct Detector {
+    /// `detector_order`で指定する名前
+    pub name: &'static str,
+    pub detect: fn(&Path) -> Option<ProjectInfo>,
+}
+
+/// 既定の優先順位で並べた検出器。先に一致したものを採用する
+pub const DETECTORS: &[Detector] = &[
+    // UnityプロジェクトはPackages/manifest.jsonなどを持つため最優先
+    Detector { name: "unity", detect: ProjectDetector::detect_unity_project },
+    Detector { name: "rust", detect: ProjectDetector::detect_rust_project },
+    Detector { name: "javascript", detect: ProjectDetector::detect_js_project },
+    Detector { name: "r", detect: ProjectDetector::detect_r_project },
+    Detector { name: "haskell", detect: ProjectDetector::detect_haskell_project },
+    Detector { name: "scala", detect: ProjectDetector::detect_scala_project },
+    Detector { name: "python", detect: ProjectDetector::detect_python_project },
+];
+
+/// `pyproject.toml`の`[tool.<名前>]` → `features`に記録するツール
+const PYTHON_TOOL_SECTIONS: &[&str] = &["poetry", "hatch", "pdm", "ruff", "black", "mypy", "pyright", "pytest"];
+
+/// `pyproject.toml`以外の設定・ロックファイル → ツール
+const PYTHON_TOOL_FILES: &[(&str, &str)] = &[
+    ("poetry.lock", "poetry"),
+    ("pdm.lock", "pdm"),
+    ("ruff.toml", "ruff"),
+    (".ruff.toml", "ruff"),
+    ("mypy.ini", "mypy"),
+    (".mypy.ini", "mypy"),
+    ("pyrightconfig.json", "pyright"),
+    ("pytest.ini", "pytest"),
+];
+
+/// この確信度を下回る検出結果は曖昧とみなす
+pub const LOW_CONFIDENCE_THRESHOLD: f32 = 0.6;
+
+/// この期間更新されていないファイル群は古いとみなす
+const STALE_THRESHOLD: Duration = Duration::from_secs(60 * 60 * 24 * 180);
+
+/// `ProjectStructure.directories`で存在を記録するディレクトリ
+const KEY_DIRECTORIES: &[&str] = &[".github", ".circleci", "docs", "examples", "benches"];
+
+/// `project_size.small_max_files`未指定時の小規模プロジェクトのファイル数
+const DEFAULT_SMALL_PROJECT_FILES: usize = 10;
+
+/// `project_size.large_min_files`未指定時の大規模プロジェクトのファイル数
+const DEFAULT_LARGE_PROJECT_FILES: usize = 500;
+
+/// 配下のファイルをテストとみなすディレクトリ名
+const TEST_DIRECTORIES: &[&str] = &["tests", "test", "__tests__", "spec", "Tests"];
+
+/// これより大きいファイルは行数を数えない（生成物・バンドル対策）
+const LOC_SIZE_LIMIT: u64 = 1024 * 1024;
+
+/// 生成ファイルとみなすファイル名の接尾辞
+const GENERATED_SUFFIXES: &[&str] = &[
+    "_generated.rs", ".generated.ts", ".generated.js", ".pb.rs", "_pb.js", "_pb.d.ts",
+    ".g.cs", ".designer.cs", ".min.js",
+];
+
+/// ファイル先頭付近にあれば生成ファイルとみなす文字列
+const GENERATED_MARKERS: &[&str] = &["Code generated", "@generated", "<auto-generated", "DO NOT EDIT"];
+
+/// `--include-generated`で生成ファイルも通常のファイルとして数えるか
+static INCLUDE_GENERATED: AtomicBool = AtomicBool::new(false);
+
+/// 生成ファイルを通常のソース・テストファイルとして数える
+pub fn include_generated_files() {
+    INCLUDE_GENERATED.store(true, Ordering::Relaxed);
+}
+
+/// `analyze --verbose`で検出・スキャンの過程を表示するか
+static TRACE: AtomicBool = AtomicBool::new(false);
+
+/// 検出・スキャンの過程の表示を有効にする
+pub fn enable_trace() {
+    TRACE.store(true, Ordering::Relaxed);
+}
+
+/// `--verbose`時のみ過程を標準エラーに出力
+fn trace(message: impl AsRef<str>) {
+    if TRACE.load(Ordering::Relaxed) {
+        eprintln!("  🔎 {}", message.as_ref());
+    }
+}
+
+/// コンテキストに含めるREADMEのキーワード数
+const README_TOPIC_LIMIT: usize = 8;
+
+/// READMEから補う説明の最大文字数
+const README_SUMMARY_CHARS: usize = 200;
+
 impl ProjectDetector {
+    /// プロジェクトを検出する。`.ccmgen.toml`の`project_type`があれば検出結果より優先する
     pub fn detect_project(path: &Path) -> Option<ProjectInfo> {
-        if let Some(cargo_info) = Self::detect_rust_project(path) {
-            Some(cargo_info)
-        } else if let Some(js_info) = Self::detect_js_project(path) {
-            Some(js_info)
-        } else {
-            None
+        Self::detect_project_with(path, ConfigManager::load_project_config(path).as_ref())
+    }
+
+    /// 読み込み済みの`.ccmgen.toml`を使ってプロジェクトを検出する
+    fn detect_project_with(path: &Path, project_config: Option<&ProjectConfig>) -> Option<ProjectInfo> {
+        let detected = Self::detectors().into_iter().find_map(|detector| {
+            let info = (detector.detect)(path)?;
+            trace(format!("検出器 {} が一致", detector.name));
+            Some(info)
+        });
+        match &detected {
+            Some(info) => trace(format!("検出: {:?} ({})", info.project_type, info.name)),
+            None => trace("検出: マニフェストが見つかりません"),
+        }
+
+        let pinned = project_config
+            .and_then(|config| config.project_type.as_deref())
+            .and_then(|value| match ProjectType::parse(value) {
+                Some(project_type) => Some(project_type),
+                None => {
+                    eprintln!("⚠️ {} の project_type が不明です: {}", PROJECT_CONFIG_FILE, value);
+                    None
+                }
+            });
+        let Some(project_type) = pinned else {
+            return detected;
+        };
+
+        let mut info = detected.unwrap_or_else(|| ProjectInfo {
+            project_type: project_type.clone(),
+            name: path
+                .canonicalize()
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+                .unwrap_or_else(|| "unknown".to_string()),
+            description: None,
+            path: path.to_path_buf(),
+            features: Vec::new(),
+            meta_framework: None,
+            bundler: None,
+            rust_edition: None,
+            rust_version: None,
+            evidence: Vec::new(),
+        });
+        trace(format!("{} の project_type で種別を上書き: {:?}", PROJECT_CONFIG_FILE, project_type));
+        info.evidence.push(format!("{} の project_type → {:?}", PROJECT_CONFIG_FILE, project_type));
+        info.project_type = project_type;
+        Some(info)
+    }
+
+    /// 設定ファイルの`detector_order`を反映した検出器の順番（未指定の検出器は既定の順番で後ろに続く）
+    pub fn detectors() -> Vec<&'static Detector> {
+        let order = ConfigManager::load_config().detector_order;
+        for name in &order {
+            if !DETECTORS.iter().any(|d| d.name == name) {
+                eprintln!("⚠️ detector_order に不明な検出器があります: {}", name);
+            }
+        }
+
+        let mut detectors: Vec<&'static Detector> = DETECTORS.iter().collect();
+        detectors.sort_by_key(|detector| {
+            order.iter().position(|name| name == detector.name).unwrap_or(order.len())
+        });
+        detectors
+    }
+
+    /// 各プロジェクト種別のマーカー一致数から候補をスコア順に返す
+    pub fn score_candidates(path: &Path) -> Vec<DetectionCandidate> {
+        let mut scores: Vec<(ProjectType, u32)> = Vec::new();
+
+        if let Ok(cargo_toml) = Self::read_cargo_toml(path) {
+            let wasm_markers = [
+                cargo_toml.get("package").and_then(|p| p.get("metadata")).and_then(|m| m.get("wasm-pack")).is_some(),
+                cargo_toml.get("dependencies").and_then(|d| d.get("wasm-bindgen")).is_some(),
+                cargo_toml
+                    .get("lib")
+                    .and_then(|l| l.get("crate-type"))
+                    .and_then(|t| t.as_array())
+                    .is_some_and(|types| types.iter().any(|t| t.as_str() == Some("cdylib"))),
+                path.join("wasm-pack.json").exists(),
+                Self::component_marker(&cargo_toml, path).is_some(),
+                Self::cargo_build_target(path).is_some_and(|t| t.starts_with("wasm32")),
+            ];
+            let wasm_score = wasm_markers.iter().filter(|m| **m).count() as u32 * 3;
+
+            scores.push((ProjectType::RustNormal, 3));
+            if wasm_score > 0 {
+                scores.push((ProjectType::RustWasm, 2 + wasm_score));
+            }
+        }
+
+        if let Ok(package_json) = Self::read_package_json(path) {
+            let has_dependency = |name: &str| {
+                ["dependencies", "devDependencies"]
+                    .iter()
+                    .any(|key| package_json.get(key).and_then(|d| d.get(name)).is_some())
+            };
+
+            scores.push((ProjectType::JavaScript, 2));
+
+            let mut typescript = 0;
+            if Self::has_typescript_config(path) {
+                typescript += 3;
+            }
+            if has_dependency("typescript") {
+                typescript += 2;
+            }
+            scores.push((ProjectType::TypeScript, typescript));
+
+            let mut node = 0;
+            if package_json
+                .get("main")
+                .and_then(|m| m.as_str())
+                .is_some_and(|m| m.ends_with(".js") || m.ends_with(".mjs"))
+            {
+                node += 1;
+            }
+            node += ["express", "fastify", "koa", "@types/node"]
+                .iter()
+                .filter(|dep| package_json.get("dependencies").and_then(|d| d.get(**dep)).is_some())
+                .count() as u32 * 2;
+            if package_json.get("engines").and_then(|e| e.get("node")).is_some() {
+                node += 2;
+            }
+            scores.push((ProjectType::NodeJs, node));
+
+            let mut bun = 0;
+            if path.join("bunfig.toml").exists() {
+                bun += 3;
+            }
+            if path.join("bun.lockb").exists() || path.join("bun.lock").exists() {
+                bun += 3;
+            }
+            if package_json.get("engines").and_then(|e| e.get("bun")).is_some() {
+                bun += 2;
+            }
+            if has_dependency("@types/bun") || has_dependency("bun-types") {
+                bun += 1;
+            }
+            scores.push((ProjectType::Bun, bun));
         }
+
+        if path.join("ProjectSettings").join("ProjectVersion.txt").exists() {
+            scores.push((ProjectType::Unity, 5));
+        }
+
+        let mut r = 0;
+        if Self::read_description_file(path).is_some() {
+            r += 3;
+        }
+        if path.join("renv.lock").exists() {
+            r += 2;
+        }
+        if path.join("tests").join("testthat").is_dir() {
+            r += 1;
+        }
+        scores.push((ProjectType::R, r));
+
+        let mut haskell = 0;
+        if Self::find_cabal_file(path).is_some() {
+            haskell += 3;
+        }
+        if path.join("package.yaml").exists() {
+            haskell += 2;
+        }
+        if path.join("stack.yaml").exists() {
+            haskell += 2;
+        }
+        scores.push((ProjectType::Haskell, haskell));
+
+        let mut scala = 0;
+        if path.join("build.sbt").exists() || path.join("build.sc").exists() {
+            scala += 3;
+        }
+        if path.join("project").join("build.properties").exists() {
+            scala += 1;
+        }
+        scores.push((ProjectType::Scala, scala));
+
+        let mut python = 0;
+        if path.join("pyproject.toml").exists() {
+            python += 3;
+        }
+        if path.join("setup.py").exists() {
+            python += 2;
+        }
+        if path.join("requirements.txt").exists() {
+            python += 1;
+        }
+        scores.push((ProjectType::Python, python));
+
+        // 同系統の種別は互いの細分化なので、確信度は別系統の候補に対してだけ計算する
+        let rivals = |project_type: &ProjectType| -> u32 {
+            scores
+                .iter()
+                .filter(|(other, _)| other.family() != project_type.family())
+                .map(|(_, score)| score)
+                .sum()
+        };
+        let mut candidates: Vec<DetectionCandidate> = scores
+            .iter()
+            .filter(|(_, score)| *score > 0)
+            .map(|(project_type, score)| DetectionCandidate {
+                project_type: project_type.clone(),
+                score: *score,
+                confidence: *score as f32 / (*score + rivals(project_type)) as f32,
+            })
+            .collect();
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.score));
+
+        candidates
+    }
+
+    /// プロジェクト種別を指定してコンテキストを作成（検出結果の手動上書き用）
+    pub fn create_project_context_as(path: &Path, project_type: ProjectType) -> Option<ProjectContext> {
+        let project_config = ConfigManager::load_project_config(path);
+        let mut info = Self::detect_project_with(path, project_config.as_ref())?;
+        info.evidence.push(format!("手動で選択 → {:?}", project_type));
+        info.project_type = project_type;
+        let structure = Self::analyze_project_structure(&mut info, project_config.as_ref());
+        let suggested_commands = Self::suggest_commands(&info, &structure);
+
+        let git = git::repository_info(path);
+
+        Some(ProjectContext {
+            info,
+            structure,
+            suggested_commands,
+            git,
+            project_config,
+        })
     }
 
-    pub fn analyze_project_structure(project: &ProjectInfo) -> ProjectStructure {
+    pub fn analyze_project_structure(project: &mut ProjectInfo, project_config: Option<&ProjectConfig>) -> ProjectStructure {
         let mut structure = ProjectStructure::new();
-        
+        if let Some(config) = project_config {
+            if !config.ignore_dirs.is_empty() {
+                trace(format!("{} の ignore_dirs: {}", PROJECT_CONFIG_FILE, config.ignore_dirs.join(", ")));
+            }
+            structure.ignore_dirs = config.ignore_dirs.clone();
+        }
+        structure.file_categories = ConfigManager::load_config()
+            .file_categories
+            .into_iter()
+            .map(|(extension, category)| (extension.trim_start_matches('.').to_string(), category))
+            .collect();
+        structure.unity = project.project_type == ProjectType::Unity;
+
         structure.scan_directory(&project.path);
+        trace(format!(
+            "スキャン完了: ソース {}個, テスト {}個, 設定 {}個, ドキュメント {}個",
+            structure.source_files.len(),
+            structure.test_files.len(),
+            structure.config_files.len(),
+            structure.doc_files.len()
+        ));
         structure.extract_metadata(project);
+        structure.extract_readme_context(project);
+        structure.container_setup = structure.detect_container_setup();
+
+        // 言語に依存しない横断的な機能の検出
+        for feature in structure.detect_cross_cutting_features(&project.path) {
+            trace(format!("機能を検出: {}", feature));
+            if !project.features.contains(&feature) {
+                project.features.push(feature);
+            }
+        }
         structure
     }
 
     pub fn create_project_context(path: &Path) -> Option<ProjectContext> {
-        let info = Self::detect_project(path)?;
-        let structure = Self::analyze_project_structure(&info);
+        let project_config = ConfigManager::load_project_config(path);
+        let mut info = Self::detect_project_with(path, project_config.as_ref())?;
+        let structure = Self::analyze_project_structure(&mut info, project_config.as_ref());
         let suggested_commands = Self::suggest_commands(&info, &structure);
 
+        let git = git::repository_info(path);
+
         Some(ProjectContext {
             info,
             structure,
             suggested_commands,
+            git,
+            project_config,
         })
     }
 
+    /// 直下の`backend`・`server`・`api`からRustのバックエンドを、`frontend`・`web`などからJS/TSのフロントエンドを探す
+    /// ルート自体がプロジェクトなら（`fuzz/`・`docs/`などを持つクレートなど）対象外。両方揃った場合のみ返す
+    pub fn detect_fullstack(path: &Path) -> Option<FullStackLayout> {
+        if Self::detect_project(path).is_some() {
+            return None;
+        }
+
+        let backend = BACKEND_DIRS
+            .iter()
+            .map(|name| path.join(name))
+            .filter(|dir| dir.join("Cargo.toml").exists())
+            .find_map(|dir| {
+                Self::create_project_context(&dir)
+                    .filter(|c| matches!(c.info.project_type, ProjectType::RustNormal | ProjectType::RustWasm))
+            });
+        let frontend = FRONTEND_DIRS
+            .iter()
+            .map(|name| path.join(name))
+            .filter(|dir| dir.join("package.json").exists())
+            .find_map(|dir| {
+                Self::create_project_context(&dir).filter(|c| {
+                    matches!(c.info.project_type,
+                        ProjectType::JavaScript | ProjectType::TypeScript | ProjectType::NodeJs | ProjectType::Bun)
+                })
+            });
+
+        let layout = FullStackLayout { backend: backend?, frontend: frontend? };
+        trace(format!(
+            "フルスタック構成: {} + {}",
+            layout.backend.info.path.display(),
+            layout.frontend.info.path.display()
+        ));
+        Some(layout)
+    }
+
+    /// 分析対象を変更ファイルに絞り込み、推奨コマンドを再計算する
+    pub fn scope_to_changed_files(context: &mut ProjectContext, changed: &[ChangedFile]) {
+        let changed_paths: HashSet<PathBuf> = changed
+            .iter()
+            .filter_map(|f| fs::canonicalize(&f.path).ok())
+            .collect();
+        context.structure.retain_files(&changed_paths);
+
+        let mut commands = Self::suggest_commands(&context.info, &context.structure);
+
+        // 新規追加されたソースファイルにテストが伴っていなければテスト生成を優先
+        let added_paths: HashSet<PathBuf> = changed
+            .iter()
+            .filter(|f| f.added)
+            .filter_map(|f| fs::canonicalize(&f.path).ok())
+            .collect();
+        let added_sources = context
+            .structure
+            .source_files
+            .iter()
+            .any(|p| fs::canonicalize(p).is_ok_and(|p| added_paths.contains(&p)));
+        if added_sources && context.structure.test_files.is_empty() {
+            commands.retain(|c| c != "generate-tests");
+            commands.insert(0, "generate-tests".to_string());
+        }
+
+        context.suggested_commands = commands;
+    }
+
     fn suggest_commands(info: &ProjectInfo, structure: &ProjectStructure) -> Vec<String> {
         let mut commands = Vec::new();
 
         // プロジェクト固有のコマンド提案
         match info.project_type {
             ProjectType::RustNormal => {
-                if !structure.test_files.is_empty() {
+                if !structure.test_files.is_empty() || !structure.inline_test_files.is_empty() {
                     commands.push("run-specific-test".to_string());
                 }
+                if !structure.example_files.is_empty() {
+                    commands.push("add-example".to_string());
+                }
+                if !structure.bench_files.is_empty() {
+                    commands.push("add-benchmark".to_string());
+                }
                 if structure.dependencies.contains_key("tokio") || structure.dependencies.contains_key("async-std") {
                     commands.push("async-refactor".to_string());
                 }
                 if structure.dependencies.contains_key("serde") {
                     commands.push("serialization-helper".to_string());
                 }
+                if info.features.iter().any(|f| f == "wasi") {
+                    // wasmtimeなどでWASIモジュールを実行するホスト側
+                    commands.push("review-wasi-imports".to_string());
+                }
+                if info.features.iter().any(|f| f == "embedded") {
+                    commands.push("review-interrupt-safety".to_string());
+                    commands.push("optimize-flash-usage".to_string());
+                    commands.push("add-hal-abstraction".to_string());
+                }
+                if info.features.iter().any(|f| f == "proc-macro") {
+                    commands.push("generate-trybuild-test".to_string());
+                    commands.push("review-span-handling".to_string());
+                    commands.push("add-derive-attribute".to_string());
+                }
             },
             ProjectType::RustWasm => {
                 commands.push("wasm-size-analysis".to_string());
-                commands.push("js-binding-generator".to_string());
+                if info.features.iter().any(|f| f == "component" || f == "wasi") {
+                    // ブラウザ向けではないためJSバインディングの代わりにWIT・WASI向けの提案
+                    commands.push("generate-wit-bindings".to_string());
+                    commands.push("add-component-export".to_string());
+                    commands.push("review-wasi-imports".to_string());
+                } else {
+                    commands.push("js-binding-generator".to_string());
+                }
                 if structure.config_files.iter().any(|p| p.file_name().unwrap_or_default() == "webpack.config.js") {
                     commands.push("webpack-wasm-optimization".to_string());
                 }
@@ -113,32 +882,190 @@ impl ProjectDetector {
                     commands.push("database-model-generator".to_string());
                 }
             },
+            ProjectType::Bun => {
+                if structure.scripts.contains_key("test") {
+                    commands.push("test-coverage-analysis".to_string());
+                }
+                if structure.dependencies.contains_key("elysia") || structure.dependencies.contains_key("hono") {
+                    commands.push("bun-route-generator".to_string());
+                }
+            },
+            ProjectType::Unity => {
+                if structure.test_files.is_empty() {
+                    commands.push("add-playmode-test".to_string());
+                }
+            },
+            ProjectType::R => {
+                if structure.test_files.is_empty() {
+                    commands.push("generate-testthat".to_string());
+                }
+            },
+            ProjectType::Haskell => {
+                if structure.test_files.is_empty() {
+                    commands.push("generate-hspec".to_string());
+                }
+            },
+            ProjectType::Scala => {
+                if structure.test_files.is_empty() {
+                    commands.push("generate-scalatest".to_string());
+                }
+            },
+            ProjectType::Python => {
+                if structure.test_files.is_empty() {
+                    commands.push("generate-pytest".to_string());
+                }
+                if info.features.iter().any(|f| f == "mypy" || f == "pyright") {
+                    commands.push("fix-type-errors".to_string());
+                }
+                if info.features.iter().any(|f| f == "ruff" || f == "black") {
+                    commands.push("fix-python-style".to_string());
+                }
+            },
+        }
+
+        // メタフレームワーク固有の提案
+        match info.meta_framework {
+            Some(MetaFramework::Next) => commands.push("add-next-route-handler".to_string()),
+            Some(MetaFramework::Nuxt) => commands.push("add-nuxt-composable".to_string()),
+            Some(MetaFramework::Astro) => commands.push("generate-astro-component".to_string()),
+            Some(MetaFramework::SvelteKit) => commands.push("add-sveltekit-route".to_string()),
+            Some(MetaFramework::Remix) | Some(MetaFramework::Qwik) | None => {}
+        }
+
+        // ファイルベースルーティング（SvelteKit/Remix/Qwik City）向けの提案
+        if info.features.iter().any(|f| f.starts_with("routing:")) {
+            commands.push("add-route-loader".to_string());
+            commands.push("generate-form-action".to_string());
+            commands.push("review-data-fetching".to_string());
+        }
+
+        // 組み込みのbundle-analysisをバンドラー向けにしたもの（RustWasmはwebpack-wasm-optimizationで扱う）
+        if info.bundler.is_some() && info.project_type != ProjectType::RustWasm {
+            commands.push("bundle-analysis".to_string());
+        }
+
+        // 横断的な機能に基づく提案
+        if info.features.iter().any(|f| f.starts_with("lint:")) {
+            commands.push("fix-lint-violations".to_string());
+        }
+        if info.features.iter().any(|f| f == "graphql") {
+            commands.push("generate-resolvers".to_string());
+            commands.push("add-dataloader".to_string());
+            commands.push("review-n-plus-one".to_string());
+        }
+        if structure.detect_database().is_some() {
+            commands.push("review-sql-injection".to_string());
+            commands.push("generate-migration".to_string());
+            commands.push("optimize-query".to_string());
+        }
+        if info.features.iter().any(|f| f == "openapi") {
+            commands.push("generate-client-from-spec".to_string());
+            commands.push("add-endpoint-to-spec".to_string());
+            commands.push("validate-against-spec".to_string());
+        }
+        if info.features.iter().any(|f| f == "docs-site") {
+            commands.push("add-doc-page".to_string());
+            commands.push("review-doc-style".to_string());
+            commands.push("generate-sidebar".to_string());
+        }
+        if info.features.iter().any(|f| f == "grpc") {
+            commands.push("generate-grpc-service-impl".to_string());
+            commands.push("add-interceptor".to_string());
+            commands.push("review-proto-compat".to_string());
         }
 
         // ファイル構造に基づく提案
-        if structure.doc_files.is_empty() {
+        if structure.doc_files.is_empty() || structure.docs_are_stale() {
             commands.push("documentation-generator".to_string());
         }
         
-        if structure.config_files.iter().any(|p| p.file_name().unwrap_or_default() == "Dockerfile") {
+        let containers = structure.container_setup();
+        if !containers.dockerfiles.is_empty() {
             commands.push("docker-optimization".to_string());
         }
+        if !containers.compose_files.is_empty() {
+            commands.push("optimize-compose".to_string());
+        }
+        if !containers.k8s_manifests.is_empty() {
+            commands.push("review-k8s-manifests".to_string());
+        }
+        let uses_containers = !containers.dockerfiles.is_empty() || !containers.compose_files.is_empty();
+        if uses_containers && containers.devcontainer.is_none() {
+            commands.push("add-devcontainer".to_string());
+        }
 
-        if structure.config_files.iter().any(|p| p.file_name().unwrap_or_default() == ".github") {
+        let has_ci_directory = [".github", ".circleci"].iter().any(|d| structure.directories.contains(*d));
+        if has_ci_directory || !structure.detect_ci_systems().is_empty() {
             commands.push("ci-cd-enhancement".to_string());
         }
 
+        let config = ConfigManager::load_config();
+
+        // 規模に応じた提案: 小規模なら雛形作り、大規模ならパフォーマンス・設計のレビュー
+        let size = structure.size(&config.project_size);
+        for command in Self::size_suggestions(info, structure, size) {
+            if !commands.iter().any(|c| c == command) {
+                commands.push(command.to_string());
+            }
+        }
+
+        commands.retain(|name| !ConfigManager::is_template_disabled(&config, name));
+
+        // 規模・関連ファイルの更新状況で並び替え（安定ソート）
+        commands.sort_by_key(|name| Self::suggestion_weight(name, structure, size));
+
+        commands
+    }
+
+    /// プロジェクトの規模に応じて追加する推奨コマンド
+    fn size_suggestions(info: &ProjectInfo, structure: &ProjectStructure, size: ProjectSize) -> Vec<&'static str> {
+        let mut commands = Vec::new();
+        match size {
+            ProjectSize::Small => {
+                if structure.readme.is_none() {
+                    commands.push("readme-generator");
+                }
+                // R・Haskell・Scalaは言語固有のテスト生成コマンドを提案済み
+                let has_generate_tests = crate::templates::TemplateManager::get_templates_for_project(info)
+                    .iter()
+                    .any(|(name, _)| *name == "generate-tests");
+                if structure.test_files.is_empty() && structure.inline_test_files.is_empty() && has_generate_tests {
+                    commands.push("generate-tests");
+                }
+            }
+            ProjectSize::Medium => {}
+            ProjectSize::Large => {
+                match info.project_type {
+                    ProjectType::RustNormal => commands.extend(["review-performance", "optimize-memory"]),
+                    ProjectType::JavaScript => commands.push("optimize-performance"),
+                    _ => {}
+                }
+                commands.push("review-architecture");
+            }
+        }
         commands
     }
 
+    /// 小さいほど優先。規模に応じた提案と古いドキュメントは前に、長く触られていないテストは後ろに回す
+    fn suggestion_weight(command: &str, structure: &ProjectStructure, size: ProjectSize) -> i32 {
+        match (command, size) {
+            ("readme-generator" | "generate-tests", ProjectSize::Small) => -2,
+            ("review-performance" | "optimize-memory" | "optimize-performance" | "review-architecture", ProjectSize::Large) => -2,
+            ("documentation-generator", _) if structure.docs_are_stale() => -1,
+            ("run-specific-test" | "test-coverage-analysis", _) if structure.is_stale(&structure.test_files) => 1,
+            _ => 0,
+        }
+    }
+
     fn detect_rust_project(path: &Path) -> Option<ProjectInfo> {
         let cargo_path = path.join("Cargo.toml");
         if !cargo_path.exists() {
             return None;
         }
 
-        let cargo_content = fs::read_to_string(&cargo_path).ok()?;
-        let cargo_toml: toml::Value = toml::from_str(&cargo_content).ok()?;
+        let cargo_toml = Self::read_cargo_toml(path)
+            .inspect_err(|e| trace(e.to_string()))
+            .ok()?;
         
         let name = cargo_toml
             .get("package")
@@ -147,14 +1074,58 @@ impl ProjectDetector {
             .unwrap_or("unknown")
             .to_string();
 
+        let description = cargo_toml
+            .get("package")
+            .and_then(|p| p.get("description"))
+            .and_then(|d| d.as_str())
+            .map(|d| d.to_string());
+
         let mut features = Vec::new();
-        let project_type = if Self::is_wasm_project(&cargo_toml, path) {
+        let mut evidence = vec!["Cargo.toml が存在 → Rust".to_string()];
+        let component = Self::component_marker(&cargo_toml, path);
+        let wasi = Self::wasi_marker(&cargo_toml, path);
+        let wasm_target = Self::cargo_build_target(path).is_some_and(|t| t.starts_with("wasm32"));
+        if let Some(reason) = &component {
+            features.push("component".to_string());
+            evidence.push(reason.clone());
+        }
+        if let Some(reason) = &wasi {
+            features.push("wasi".to_string());
+            evidence.push(reason.clone());
+        }
+
+        let project_type = if let Some(reason) = Self::wasm_marker(&cargo_toml, path) {
+            features.push("wasm".to_string());
+            evidence.push(reason);
+            ProjectType::RustWasm
+        } else if component.is_some() || wasm_target {
             features.push("wasm".to_string());
+            evidence.push("Component Model / wasm32 ターゲット → RustWasm".to_string());
             ProjectType::RustWasm
         } else {
+            evidence.push("WASM のマーカーなし → RustNormal".to_string());
             ProjectType::RustNormal
         };
 
+        let is_proc_macro = cargo_toml
+            .get("lib")
+            .and_then(|lib| lib.get("proc-macro"))
+            .and_then(|p| p.as_bool())
+            .unwrap_or(false);
+        if is_proc_macro {
+            features.push("proc-macro".to_string());
+            evidence.push("[lib] proc-macro = true → 手続きマクロクレート".to_string());
+        }
+
+        if let Some(reason) = Self::no_std_marker(path) {
+            features.push("no_std".to_string());
+            evidence.push(reason);
+        }
+        if let Some(reason) = Self::embedded_marker(&cargo_toml, path) {
+            features.push("embedded".to_string());
+            evidence.push(reason);
+        }
+
         if cargo_toml.get("dependencies").is_some() {
             features.push("dependencies".to_string());
         }
@@ -162,22 +1133,374 @@ impl ProjectDetector {
             features.push("dev-dependencies".to_string());
         }
 
+        let rust_edition = Self::cargo_package_field(&cargo_toml, path, "edition");
+        let rust_version = Self::cargo_package_field(&cargo_toml, path, "rust-version");
+        if let Some(edition) = &rust_edition {
+            evidence.push(format!("package.edition = {}", edition));
+        }
+        if let Some(msrv) = &rust_version {
+            evidence.push(format!("package.rust-version = {} → MSRV", msrv));
+        }
+
         Some(ProjectInfo {
             project_type,
             name,
+            description,
             path: path.to_path_buf(),
             features,
+            meta_framework: None,
+            bundler: None,
+            rust_edition,
+            rust_version,
+            evidence,
         })
     }
 
-    fn detect_js_project(path: &Path) -> Option<ProjectInfo> {
-        let package_path = path.join("package.json");
-        if !package_path.exists() {
+    /// `[package]`の文字列フィールドを取得（`key.workspace = true`ならワークスペースルートの`[workspace.package]`から）
+    fn cargo_package_field(cargo_toml: &toml::Value, path: &Path, key: &str) -> Option<String> {
+        let value = cargo_toml.get("package")?.get(key)?;
+        if let Some(v) = value.as_str() {
+            return Some(v.to_string());
+        }
+        if !value.get("workspace").and_then(|w| w.as_bool()).unwrap_or(false) {
             return None;
         }
-
-        let package_content = fs::read_to_string(&package_path).ok()?;
-        let package_json: serde_json::Value = serde_json::from_str(&package_content).ok()?;
+        path.ancestors().find_map(|dir| {
+            let content = fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+            let root = toml::from_str::<toml::Value>(&content).ok()?;
+            let package = root.get("workspace")?.get("package")?;
+            package.get(key)?.as_str().map(|v| v.to_string())
+        })
+    }
+
+    /// `ProjectSettings/ProjectVersion.txt`の有無でUnityプロジェクトを判定
+    fn detect_unity_project(path: &Path) -> Option<ProjectInfo> {
+        let version_path = path.join("ProjectSettings").join("ProjectVersion.txt");
+        let version_content = fs::read_to_string(&version_path).ok()?;
+
+        let name = path
+            .canonicalize()
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let editor_version = version_content
+            .lines()
+            .find_map(|line| line.strip_prefix("m_EditorVersion:"))
+            .map(|v| v.trim().to_string());
+
+        let mut evidence = vec![match &editor_version {
+            Some(version) => format!("ProjectSettings/ProjectVersion.txt が存在（Unity {}） → Unity", version),
+            None => "ProjectSettings/ProjectVersion.txt が存在 → Unity".to_string(),
+        }];
+        if path.join("Assets").is_dir() {
+            evidence.push("Assets/ ディレクトリが存在".to_string());
+        }
+
+        Some(ProjectInfo {
+            project_type: ProjectType::Unity,
+            name,
+            description: editor_version.map(|v| format!("Unity {} project", v)),
+            path: path.to_path_buf(),
+            features: vec!["unity".to_string()],
+            meta_framework: None,
+            bundler: None,
+            rust_edition: None,
+            rust_version: None,
+            evidence,
+        })
+    }
+
+    /// `DESCRIPTION`（`Package:`フィールドを持つもの）または`renv.lock`の有無でRプロジェクトを判定
+    fn detect_r_project(path: &Path) -> Option<ProjectInfo> {
+        let description = Self::read_description_file(path);
+        let has_renv = path.join("renv.lock").exists();
+        if description.is_none() && !has_renv {
+            return None;
+        }
+
+        let mut evidence = Vec::new();
+        let mut features = Vec::new();
+        if description.is_some() {
+            evidence.push("DESCRIPTION が存在（Package フィールドあり） → R".to_string());
+        }
+        if has_renv {
+            evidence.push("renv.lock が存在 → R".to_string());
+            features.push("renv".to_string());
+        }
+        if path.join("tests").join("testthat").is_dir() {
+            evidence.push("tests/testthat/ ディレクトリが存在".to_string());
+            features.push("testthat".to_string());
+        }
+
+        let field = |key: &str| description.as_ref().and_then(|fields| fields.get(key).cloned());
+        let name = field("Package").unwrap_or_else(|| {
+            path.canonicalize()
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+                .unwrap_or_else(|| "unknown".to_string())
+        });
+
+        Some(ProjectInfo {
+            project_type: ProjectType::R,
+            name,
+            description: field("Title").or_else(|| field("Description")),
+            path: path.to_path_buf(),
+            features,
+            meta_framework: None,
+            bundler: None,
+            rust_edition: None,
+            rust_version: None,
+            evidence,
+        })
+    }
+
+    /// Rパッケージの`DESCRIPTION`（DCF形式）を読む。`Package:`フィールドが無ければNone
+    ///
+    /// 空白で始まる行は直前のフィールドの続きとして1行にまとめる
+    fn read_description_file(path: &Path) -> Option<HashMap<String, String>> {
+        let content = fs::read_to_string(path.join("DESCRIPTION")).ok()?;
+        let mut fields: HashMap<String, String> = HashMap::new();
+        let mut current: Option<String> = None;
+        for line in content.lines() {
+            if line.starts_with([' ', '\t']) {
+                if let Some(value) = current.as_ref().and_then(|key| fields.get_mut(key)) {
+                    value.push(' ');
+                    value.push_str(line.trim());
+                }
+            } else if let Some((key, value)) = line.split_once(':') {
+                fields.insert(key.trim().to_string(), value.trim().to_string());
+                current = Some(key.trim().to_string());
+            }
+        }
+        fields.contains_key("Package").then_some(fields)
+    }
+
+    /// `path`直下の`Cargo.toml`を読み込む
+    pub fn read_cargo_toml(path: &Path) -> Result<toml::Value, CcmgenError> {
+        let manifest = path.join("Cargo.toml");
+        let content = fs::read_to_string(&manifest)?;
+        toml::from_str(&content).map_err(|e| CcmgenError::ManifestParse { path: manifest, message: e.to_string() })
+    }
+
+    /// `path`直下の`package.json`を読み込む
+    pub fn read_package_json(path: &Path) -> Result<serde_json::Value, CcmgenError> {
+        let manifest = path.join("package.json");
+        let content = fs::read_to_string(&manifest)?;
+        serde_json::from_str(&content).map_err(|e| CcmgenError::ManifestParse { path: manifest, message: e.to_string() })
+    }
+
+    /// `*.cabal`・`package.yaml`（hpack）・`stack.yaml`の有無でHaskellプロジェクトを判定
+    fn detect_haskell_project(path: &Path) -> Option<ProjectInfo> {
+        let cabal_file = Self::find_cabal_file(path);
+        let has_hpack = path.join("package.yaml").exists();
+        let has_stack = path.join("stack.yaml").exists();
+        if cabal_file.is_none() && !has_hpack && !has_stack {
+            return None;
+        }
+
+        let mut evidence = Vec::new();
+        let mut features = Vec::new();
+        if let Some(cabal_file) = &cabal_file {
+            evidence.push(format!("{} が存在 → Haskell", cabal_file.file_name().unwrap_or_default().to_string_lossy()));
+        }
+        if has_hpack {
+            evidence.push("package.yaml が存在 → Haskell".to_string());
+            features.push("hpack".to_string());
+        }
+        if has_stack {
+            evidence.push("stack.yaml が存在 → Haskell".to_string());
+            features.push("stack".to_string());
+        }
+
+        // hpackの場合は.cabalが生成物なのでpackage.yamlを優先する
+        let manifest = if has_hpack { Some(path.join("package.yaml")) } else { cabal_file };
+        let content = manifest.and_then(|m| fs::read_to_string(m).ok()).unwrap_or_default();
+        let field = |key: &str| {
+            content.lines().find_map(|line| {
+                let (k, value) = line.split_once(':')?;
+                let value = value.trim().trim_matches(['"', '\'']);
+                (k.trim().eq_ignore_ascii_case(key) && !line.starts_with([' ', '\t']) && !value.is_empty())
+                    .then(|| value.to_string())
+            })
+        };
+        let name = field("name").unwrap_or_else(|| {
+            path.canonicalize()
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+                .unwrap_or_else(|| "unknown".to_string())
+        });
+
+        Some(ProjectInfo {
+            project_type: ProjectType::Haskell,
+            name,
+            description: field("synopsis"),
+            path: path.to_path_buf(),
+            features,
+            meta_framework: None,
+            bundler: None,
+            rust_edition: None,
+            rust_version: None,
+            evidence,
+        })
+    }
+
+    /// `build.sbt`（sbt）・`build.sc`（mill）の有無でScalaプロジェクトを判定
+    fn detect_scala_project(path: &Path) -> Option<ProjectInfo> {
+        let (build_file, tool) = if path.join("build.sbt").exists() {
+            ("build.sbt", "sbt")
+        } else if path.join("build.sc").exists() {
+            ("build.sc", "mill")
+        } else {
+            return None;
+        };
+
+        let content = fs::read_to_string(path.join(build_file)).unwrap_or_default();
+        let mut evidence = vec![format!("{} が存在 → Scala", build_file)];
+        let mut features = vec![tool.to_string()];
+        if path.join("project").join("build.properties").exists() {
+            evidence.push("project/build.properties が存在 → sbt".to_string());
+        }
+        if Self::sbt_setting(&content, "scalaVersion").is_some_and(|v| v.starts_with('3')) {
+            features.push("scala3".to_string());
+        }
+
+        // millはビルド定義のモジュール名（`object core extends ScalaModule`）を名前とする
+        let name = match tool {
+            "sbt" => Self::sbt_setting(&content, "name"),
+            _ => content.lines().find_map(|line| {
+                let rest = line.trim().strip_prefix("object ")?;
+                let (module, extends) = rest.split_once(" extends ")?;
+                extends.contains("Module").then(|| module.trim().to_string())
+            }),
+        }
+        .unwrap_or_else(|| {
+            path.canonicalize()
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+                .unwrap_or_else(|| "unknown".to_string())
+        });
+
+        Some(ProjectInfo {
+            project_type: ProjectType::Scala,
+            name,
+            description: Self::sbt_setting(&content, "description"),
+            path: path.to_path_buf(),
+            features,
+            meta_framework: None,
+            bundler: None,
+            rust_edition: None,
+            rust_version: None,
+            evidence,
+        })
+    }
+
+    /// `pyproject.toml`・`setup.py`・`requirements.txt`の有無でPythonプロジェクトを判定
+    ///
+    /// `pyproject.toml`の`[tool.*]`と設定ファイルからツールチェーン（poetry、mypyなど）を`features`に記録する
+    fn detect_python_project(path: &Path) -> Option<ProjectInfo> {
+        let markers: Vec<&str> = ["pyproject.toml", "setup.py", "requirements.txt"]
+            .into_iter()
+            .filter(|marker| path.join(marker).exists())
+            .collect();
+        if markers.is_empty() {
+            return None;
+        }
+
+        // 壊れたpyproject.tomlでもPythonプロジェクトとして扱い、名前などはディレクトリ名から補う
+        let pyproject = if markers.contains(&"pyproject.toml") {
+            Self::read_pyproject(path).inspect_err(|e| trace(e.to_string())).ok()
+        } else {
+            None
+        };
+        let mut evidence: Vec<String> = markers.iter().map(|marker| format!("{} が存在 → Python", marker)).collect();
+        let mut features: Vec<String> = Vec::new();
+
+        let project = pyproject.as_ref().and_then(|p| p.get("project"));
+        let tool = pyproject.as_ref().and_then(|p| p.get("tool"));
+        let poetry = tool.and_then(|t| t.get("poetry"));
+        for section in PYTHON_TOOL_SECTIONS {
+            if tool.and_then(|t| t.get(*section)).is_some() {
+                features.push(section.to_string());
+                evidence.push(format!("[tool.{}] → {}", section, section));
+            }
+        }
+        for (file, tool_name) in PYTHON_TOOL_FILES {
+            if path.join(file).exists() && !features.iter().any(|f| f == tool_name) {
+                features.push(tool_name.to_string());
+                evidence.push(format!("{} が存在 → {}", file, tool_name));
+            }
+        }
+
+        let field = |key: &str| {
+            project
+                .and_then(|p| p.get(key))
+                .or_else(|| poetry.and_then(|p| p.get(key)))
+                .and_then(|v| v.as_str())
+                .map(|v| v.to_string())
+        };
+        let name = field("name").unwrap_or_else(|| {
+            path.canonicalize()
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+                .unwrap_or_else(|| "unknown".to_string())
+        });
+
+        Some(ProjectInfo {
+            project_type: ProjectType::Python,
+            name,
+            description: field("description"),
+            path: path.to_path_buf(),
+            features,
+            meta_framework: None,
+            bundler: None,
+            rust_edition: None,
+            rust_version: None,
+            evidence,
+        })
+    }
+
+    /// `path`直下の`pyproject.toml`を読み込む
+    pub fn read_pyproject(path: &Path) -> Result<toml::Value, CcmgenError> {
+        let manifest = path.join("pyproject.toml");
+        let content = fs::read_to_string(&manifest)?;
+        toml::from_str(&content).map_err(|e| CcmgenError::ManifestParse { path: manifest, message: e.to_string() })
+    }
+
+    /// `build.sbt`の`key := "value"`（`ThisBuild / key`も含む）の値
+    fn sbt_setting(content: &str, key: &str) -> Option<String> {
+        content.lines().find_map(|line| {
+            let (lhs, rhs) = line.split_once(":=")?;
+            let lhs = lhs.trim();
+            let lhs = lhs.rsplit_once('/').map_or(lhs, |(_, k)| k.trim());
+            let value = rhs.trim().trim_end_matches(',').trim();
+            (lhs == key && value.starts_with('"'))
+                .then(|| value.trim_matches('"').to_string())
+                .filter(|v| !v.is_empty())
+        })
+    }
+
+    /// プロジェクト直下の`*.cabal`（複数あれば名前順で最初のもの）
+    fn find_cabal_file(path: &Path) -> Option<PathBuf> {
+        let mut cabal_files: Vec<PathBuf> = fs::read_dir(path)
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "cabal"))
+            .collect();
+        cabal_files.sort();
+        cabal_files.into_iter().next()
+    }
+
+    fn detect_js_project(path: &Path) -> Option<ProjectInfo> {
+        let package_path = path.join("package.json");
+        if !package_path.exists() {
+            return None;
+        }
+
+        let package_json = Self::read_package_json(path)
+            .inspect_err(|e| trace(e.to_string()))
+            .ok()?;
         
         let name = package_json
             .get("name")
@@ -185,14 +1508,31 @@ impl ProjectDetector {
             .unwrap_or("unknown")
             .to_string();
 
+        let description = package_json
+            .get("description")
+            .and_then(|d| d.as_str())
+            .filter(|d| !d.is_empty())
+            .map(|d| d.to_string());
+
         let mut features = Vec::new();
-        let project_type = if Self::has_typescript_config(path) {
+        let mut evidence = vec!["package.json が存在 → JavaScript 系".to_string()];
+        let project_type = if let Some(reason) = Self::bun_marker(&package_json, path) {
+            features.push("bun".to_string());
+            evidence.push(reason);
+            if Self::has_typescript_config(path) {
+                features.push("typescript".to_string());
+            }
+            ProjectType::Bun
+        } else if let Some(reason) = Self::typescript_marker(path) {
             features.push("typescript".to_string());
+            evidence.push(reason);
             ProjectType::TypeScript
-        } else if Self::is_node_project(&package_json) {
+        } else if let Some(reason) = Self::node_marker(&package_json) {
             features.push("nodejs".to_string());
+            evidence.push(reason);
             ProjectType::NodeJs
         } else {
+            evidence.push("TypeScript/Node.js/Bun のマーカーなし → JavaScript".to_string());
             ProjectType::JavaScript
         };
 
@@ -206,55 +1546,252 @@ impl ProjectDetector {
             features.push("scripts".to_string());
         }
 
+        let meta_framework = Self::detect_meta_framework(&package_json, path);
+        if let Some(framework) = &meta_framework {
+            evidence.push(format!("フレームワークの設定ファイルまたは依存関係 → {:?}", framework));
+            if let Some((routes_dir, key)) = framework.routing_convention()
+                && path.join(routes_dir).is_dir()
+            {
+                features.push(format!("routing:{}", key));
+                evidence.push(format!("{}/ が存在 → {} のファイルベースルーティング", routes_dir, key));
+            }
+        }
+
+        let bundler = Self::detect_bundler(&package_json, path);
+        if let Some(bundler) = &bundler {
+            evidence.push(format!("バンドラーの設定ファイルまたは依存関係 → {:?}", bundler));
+        }
+
         Some(ProjectInfo {
             project_type,
             name,
+            description,
             path: path.to_path_buf(),
             features,
+            meta_framework,
+            bundler,
+            rust_edition: None,
+            rust_version: None,
+            evidence,
+        })
+    }
+
+    fn detect_bundler(package_json: &serde_json::Value, path: &Path) -> Option<Bundler> {
+        let has_dependency = |name: &str| {
+            ["dependencies", "devDependencies"]
+                .iter()
+                .any(|key| package_json.get(key).and_then(|d| d.get(name)).is_some())
+        };
+        let has_config = |base: &str| {
+            ["js", "mjs", "cjs", "ts", "mts"]
+                .iter()
+                .any(|ext| path.join(format!("{base}.{ext}")).exists())
+        };
+
+        if has_config("vite.config") || has_dependency("vite") {
+            Some(Bundler::Vite)
+        } else if has_config("webpack.config") || has_dependency("webpack") {
+            Some(Bundler::Webpack)
+        } else if has_config("rollup.config") || has_dependency("rollup") {
+            Some(Bundler::Rollup)
+        } else if has_config("esbuild.config") || has_dependency("esbuild") {
+            Some(Bundler::Esbuild)
+        } else {
+            None
+        }
+    }
+
+    fn detect_meta_framework(package_json: &serde_json::Value, path: &Path) -> Option<MetaFramework> {
+        let has_dependency = |name: &str| {
+            ["dependencies", "devDependencies"]
+                .iter()
+                .any(|key| package_json.get(key).and_then(|d| d.get(name)).is_some())
+        };
+        let has_config = |base: &str| {
+            ["js", "mjs", "cjs", "ts", "mts"]
+                .iter()
+                .any(|ext| path.join(format!("{base}.{ext}")).exists())
+        };
+
+        if has_config("next.config") || has_dependency("next") {
+            Some(MetaFramework::Next)
+        } else if has_config("nuxt.config") || has_dependency("nuxt") {
+            Some(MetaFramework::Nuxt)
+        } else if has_config("astro.config") || has_dependency("astro") {
+            Some(MetaFramework::Astro)
+        } else if has_config("svelte.config") || has_dependency("@sveltejs/kit") {
+            Some(MetaFramework::SvelteKit)
+        } else if has_config("remix.config") || has_dependency("@remix-run/react") || has_dependency("@remix-run/node") {
+            Some(MetaFramework::Remix)
+        } else if has_dependency("@builder.io/qwik-city") || has_dependency("@builder.io/qwik") {
+            Some(MetaFramework::Qwik)
+        } else {
+            None
+        }
+    }
+
+    /// クレートルートに`#![no_std]`があればその根拠
+    fn no_std_marker(path: &Path) -> Option<String> {
+        ["src/lib.rs", "src/main.rs"].iter().find_map(|root| {
+            let content = fs::read_to_string(path.join(root)).ok()?;
+            content
+                .lines()
+                .any(|line| {
+                    let line = line.trim();
+                    line.starts_with("#![no_std]") || (line.starts_with("#![cfg_attr(") && line.contains("no_std"))
+                })
+                .then(|| format!("{} に #![no_std] → no_std", root))
         })
     }
 
-    fn is_wasm_project(cargo_toml: &toml::Value, path: &Path) -> bool {
+    /// 組み込み向けと判定した根拠（memory.x、ベアメタルのターゲット、組み込み向け依存関係）
+    fn embedded_marker(cargo_toml: &toml::Value, path: &Path) -> Option<String> {
+        if path.join("memory.x").exists() {
+            return Some("memory.x が存在 → 組み込み".to_string());
+        }
+
+        if let Some(target) = Self::cargo_build_target(path)
+            && ["thumbv", "riscv32", "xtensa-", "avr-"].iter().any(|prefix| target.starts_with(prefix))
+        {
+            return Some(format!(".cargo/config.toml の build.target = {} → 組み込み", target));
+        }
+
+        let dependencies = cargo_toml.get("dependencies").and_then(|d| d.as_table())?;
+        ["embedded-hal", "cortex-m", "cortex-m-rt", "riscv-rt", "embassy-executor", "esp-hal"]
+            .iter()
+            .find(|name| dependencies.contains_key(**name))
+            .map(|name| format!("{} 依存 → 組み込み", name))
+    }
+
+    /// `.cargo/config.toml`の`build.target`
+    fn cargo_build_target(path: &Path) -> Option<String> {
+        let content = fs::read_to_string(path.join(".cargo").join("config.toml"))
+            .or_else(|_| fs::read_to_string(path.join(".cargo").join("config")))
+            .ok()?;
+        let config = toml::from_str::<toml::Value>(&content).ok()?;
+        config.get("build")?.get("target")?.as_str().map(|t| t.to_string())
+    }
+
+    /// WebAssembly Component Modelのクレートと判定した根拠（cargo-component、wit-bindgen、WASM向けのビルド設定を伴うwit/）
+    fn component_marker(cargo_toml: &toml::Value, path: &Path) -> Option<String> {
+        if cargo_toml
+            .get("package")
+            .and_then(|p| p.get("metadata"))
+            .and_then(|m| m.get("component"))
+            .is_some()
+        {
+            return Some("[package.metadata.component] → Component Model".to_string());
+        }
+        if let Some(dependency) = ["wit-bindgen", "wit-bindgen-rt", "cargo-component-bindings"]
+            .into_iter()
+            .find(|name| cargo_toml.get("dependencies").and_then(|d| d.get(name)).is_some())
+        {
+            return Some(format!("dependencies に {} → Component Model", dependency));
+        }
+
+        // WITはWASM以外のツールでも使われるため、wit/ だけでなくWASM向けのビルド設定も必要
+        let wit = if path.join("wit").is_dir() {
+            "wit/"
+        } else if fs::read_dir(path).ok()?.flatten().any(|entry| entry.path().extension().is_some_and(|e| e == "wit")) {
+            "*.wit"
+        } else {
+            return None;
+        };
+        if Self::cargo_build_target(path).is_some_and(|t| t.starts_with("wasm32")) {
+            return Some(format!("{} と wasm32 ターゲット → Component Model", wit));
+        }
+        cargo_toml
+            .get("lib")
+            .and_then(|l| l.get("crate-type"))
+            .and_then(|t| t.as_array())
+            .is_some_and(|types| types.iter().any(|t| t.as_str() == Some("cdylib")))
+            .then(|| format!("{} と crate-type に cdylib → Component Model", wit))
+    }
+
+    /// WASIを利用していると判定した根拠（WASIターゲット、wasi/wasmtime依存）
+    fn wasi_marker(cargo_toml: &toml::Value, path: &Path) -> Option<String> {
+        if let Some(target) = Self::cargo_build_target(path)
+            && target.starts_with("wasm32-wasi")
+        {
+            return Some(format!(".cargo/config.toml の build.target = {} → WASI", target));
+        }
+        let dependencies = cargo_toml.get("dependencies").and_then(|d| d.as_table())?;
+        ["wasi", "wasmtime", "wasmtime-wasi", "wasi-common"]
+            .iter()
+            .find(|name| dependencies.contains_key(**name))
+            .map(|name| format!("{} 依存 → WASI", name))
+    }
+
+    /// WASMプロジェクトと判定した根拠（該当しなければNone）
+    fn wasm_marker(cargo_toml: &toml::Value, path: &Path) -> Option<String> {
         // Check for wasm-pack configuration
         if cargo_toml.get("package")
             .and_then(|p| p.get("metadata"))
             .and_then(|m| m.get("wasm-pack"))
             .is_some() {
-            return true;
+            return Some("[package.metadata.wasm-pack] → RustWasm".to_string());
         }
 
         // Check for wasm-bindgen dependency
-        if let Some(deps) = cargo_toml.get("dependencies") {
-            if deps.get("wasm-bindgen").is_some() {
-                return true;
-            }
+        if let Some(deps) = cargo_toml.get("dependencies")
+            && deps.get("wasm-bindgen").is_some()
+        {
+            return Some("dependencies に wasm-bindgen → RustWasm".to_string());
         }
 
         // Check for lib crate-type
-        if let Some(lib) = cargo_toml.get("lib") {
-            if let Some(crate_type) = lib.get("crate-type") {
-                if let Some(types) = crate_type.as_array() {
-                    return types.iter().any(|t| t.as_str() == Some("cdylib"));
-                }
-            }
+        if let Some(lib) = cargo_toml.get("lib")
+            && let Some(crate_type) = lib.get("crate-type")
+            && let Some(types) = crate_type.as_array()
+        {
+            return types
+                .iter()
+                .any(|t| t.as_str() == Some("cdylib"))
+                .then(|| "crate-type に cdylib → RustWasm".to_string());
         }
 
         // Check for wasm-pack.json
-        path.join("wasm-pack.json").exists()
+        path.join("wasm-pack.json")
+            .exists()
+            .then(|| "wasm-pack.json が存在 → RustWasm".to_string())
+    }
+
+    /// TypeScript設定ファイルの根拠（該当しなければNone）
+    fn typescript_marker(path: &Path) -> Option<String> {
+        ["tsconfig.json", "tsconfig.build.json", "typescript.json"]
+            .iter()
+            .find(|file| path.join(file).exists())
+            .map(|file| format!("{} が存在 → TypeScript", file))
     }
 
     fn has_typescript_config(path: &Path) -> bool {
-        path.join("tsconfig.json").exists() || 
-        path.join("tsconfig.build.json").exists() ||
-        path.join("typescript.json").exists()
+        Self::typescript_marker(path).is_some()
+    }
+
+    /// Bunプロジェクトと判定した根拠（該当しなければNone）
+    fn bun_marker(package_json: &serde_json::Value, path: &Path) -> Option<String> {
+        // Check for Bun specific files
+        if let Some(file) = ["bunfig.toml", "bun.lockb", "bun.lock"]
+            .iter()
+            .find(|file| path.join(file).exists())
+        {
+            return Some(format!("{} が存在 → Bun", file));
+        }
+
+        // Check for engines field
+        package_json.get("engines")
+            .and_then(|e| e.get("bun"))
+            .map(|_| "package.json の engines.bun → Bun".to_string())
     }
 
-    fn is_node_project(package_json: &serde_json::Value) -> bool {
+    /// Node.jsプロジェクトと判定した根拠（該当しなければNone）
+    fn node_marker(package_json: &serde_json::Value) -> Option<String> {
         // Check for Node.js specific fields
-        if let Some(main) = package_json.get("main") {
-            if let Some(main_str) = main.as_str() {
-                return main_str.ends_with(".js") || main_str.ends_with(".mjs");
-            }
+        if let Some(main) = package_json.get("main")
+            && let Some(main_str) = main.as_str()
+        {
+            return (main_str.ends_with(".js") || main_str.ends_with(".mjs"))
+                .then(|| format!("package.json の main が {} → NodeJs", main_str));
         }
 
         // Check for Node.js dependencies
@@ -262,19 +1799,15 @@ impl ProjectDetector {
             let node_deps = ["express", "fastify", "koa", "@types/node"];
             for dep in node_deps {
                 if deps.get(dep).is_some() {
-                    return true;
+                    return Some(format!("dependencies に {} → NodeJs", dep));
                 }
             }
         }
 
         // Check for engines field
-        if package_json.get("engines")
+        package_json.get("engines")
             .and_then(|e| e.get("node"))
-            .is_some() {
-            return true;
-        }
-
-        false
+            .map(|_| "package.json の engines.node → NodeJs".to_string())
     }
 }
 
@@ -283,61 +1816,228 @@ impl ProjectStructure {
         Self {
             source_files: Vec::new(),
             test_files: Vec::new(),
+            inline_test_files: Vec::new(),
+            example_files: Vec::new(),
+            bench_files: Vec::new(),
             config_files: Vec::new(),
             doc_files: Vec::new(),
             dependencies: HashMap::new(),
             scripts: HashMap::new(),
             entry_points: Vec::new(),
+            schema_files: Vec::new(),
+            file_mtimes: HashMap::new(),
+            loc_by_extension: HashMap::new(),
+            directories: HashSet::new(),
+            generated_files: Vec::new(),
+            readme: None,
+            license_files: Vec::new(),
+            readme_topics: Vec::new(),
+            ignore_dirs: Vec::new(),
+            file_categories: HashMap::new(),
+            unity: false,
+            container_setup: ContainerSetup::default(),
+        }
+    }
+
+    /// 依存関係毎に、参照しているソース・テストファイル数を数える
+    pub fn dependency_reference_counts(&self) -> HashMap<String, usize> {
+        let mut counts: HashMap<String, usize> = self.dependencies.keys().map(|name| (name.clone(), 0)).collect();
+        for file in self.source_files.iter().chain(&self.test_files) {
+            let Ok(content) = fs::read_to_string(file) else {
+                continue;
+            };
+            for (name, count) in counts.iter_mut() {
+                if references_dependency(&content, name) {
+                    *count += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// READMEからキーワードを抽出し、マニフェストに説明が無ければ最初の段落を説明とする
+    fn extract_readme_context(&mut self, project: &mut ProjectInfo) {
+        let Some(readme) = &self.readme else {
+            return;
+        };
+        let Ok(bytes) = fs::read(readme) else {
+            return;
+        };
+        let content = String::from_utf8_lossy(&bytes);
+        trace(format!("メタデータ抽出: {}", readme.display()));
+
+        self.readme_topics = docs::readme_keywords(&content, README_TOPIC_LIMIT);
+        if project.description.is_none() {
+            project.description = docs::readme_summary(&content, README_SUMMARY_CHARS);
         }
     }
 
     pub fn scan_directory(&mut self, path: &Path) {
+        self.scan_tree(path, path);
+        self.license_files.sort();
+    }
+
+    /// プロジェクト直下のREADME・ライセンスファイルを記録
+    fn record_root_document(&mut self, path: &Path) {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            return;
+        };
+        let upper = name.to_uppercase();
+        let (stem, extension) = upper.split_once('.').unwrap_or((&upper, ""));
+
+        if stem == "README" {
+            // 複数ある場合は .md > .rst > .txt の順で採用
+            let rank = |ext: &str| ["MD", "RST", "TXT"].iter().position(|e| *e == ext);
+            let current = self.readme.as_ref().and_then(|p| {
+                let ext = p.extension()?.to_str()?.to_uppercase();
+                rank(&ext)
+            });
+            if let Some(new_rank) = rank(extension)
+                && current.is_none_or(|current| new_rank < current)
+            {
+                self.readme = Some(path.to_path_buf());
+            }
+        } else if (stem.starts_with("LICENSE") || stem.starts_with("LICENCE") || stem == "COPYING")
+            && matches!(extension, "" | "MD" | "TXT")
+        {
+            self.license_files.push(path.to_path_buf());
+        }
+    }
+
+    /// ライセンスファイルの本文からライセンスの種類を推定（デュアルライセンスは` OR `で連結）
+    pub fn detect_license(&self) -> Option<String> {
+        let licenses: Vec<&str> = self
+            .license_files
+            .iter()
+            .filter_map(|path| fs::read(path).ok())
+            .filter_map(|bytes| Self::identify_license(&String::from_utf8_lossy(&bytes)))
+            .fold(Vec::new(), |mut licenses, license| {
+                if !licenses.contains(&license) {
+                    licenses.push(license);
+                }
+                licenses
+            });
+        (!licenses.is_empty()).then(|| licenses.join(" OR "))
+    }
+
+    fn identify_license(text: &str) -> Option<&'static str> {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let has = |needle: &str| text.contains(needle);
+
+        if has("Apache License") && has("Version 2.0") {
+            Some("Apache-2.0")
+        } else if has("GNU AFFERO GENERAL PUBLIC LICENSE") {
+            Some("AGPL-3.0")
+        } else if has("GNU LESSER GENERAL PUBLIC LICENSE") {
+            Some(if has("Version 2.1") { "LGPL-2.1" } else { "LGPL-3.0" })
+        } else if has("GNU GENERAL PUBLIC LICENSE") {
+            Some(if has("Version 2,") || has("Version 2 ") { "GPL-2.0" } else { "GPL-3.0" })
+        } else if has("Mozilla Public License") {
+            Some("MPL-2.0")
+        } else if has("This is free and unencumbered software") {
+            Some("Unlicense")
+        } else if has("Permission to use, copy, modify, and/or distribute this software") {
+            Some("ISC")
+        } else if has("Permission is hereby granted, free of charge") {
+            Some("MIT")
+        } else if has("Redistribution and use in source and binary forms") {
+            Some(if has("Neither the name") { "BSD-3-Clause" } else { "BSD-2-Clause" })
+        } else {
+            None
+        }
+    }
+
+    fn scan_tree(&mut self, root: &Path, path: &Path) {
+        trace(format!("スキャン: {}", path.display()));
         if let Ok(entries) = fs::read_dir(path) {
             for entry in entries.flatten() {
                 let path = entry.path();
                 if path.is_file() {
-                    self.categorize_file(&path);
-                } else if path.is_dir() && !self.should_skip_directory(&path) {
-                    self.scan_directory(&path);
+                    if let Ok(modified) = entry.metadata().and_then(|m| m.modified()) {
+                        self.file_mtimes.insert(path.clone(), modified);
+                    }
+                    if path.parent() == Some(root) {
+                        self.record_root_document(&path);
+                    }
+                    self.categorize_file(root, &path);
+                } else if path.is_dir() && self.should_skip_directory(&path) {
+                    trace(format!("スキップ（生成物・依存ディレクトリ）: {}", path.display()));
+                } else if path.is_dir() && self.is_ignored(root, &path) {
+                    trace(format!("スキップ（{} の ignore_dirs）: {}", PROJECT_CONFIG_FILE, path.display()));
+                } else if path.is_dir() {
+                    if path.parent() == Some(root)
+                        && let Some(name) = path.file_name().and_then(|n| n.to_str())
+                        && KEY_DIRECTORIES.contains(&name)
+                    {
+                        self.directories.insert(name.to_string());
+                    }
+                    self.scan_tree(root, &path);
                 }
             }
         }
     }
 
-    fn categorize_file(&mut self, path: &Path) {
+    /// 拡張子の組み込みの分類
+    fn builtin_category(extension: &str) -> Option<FileCategory> {
+        match extension {
+            "rs" | "js" | "jsx" | "ts" | "tsx" | "cs" | "R" | "r" | "hs" | "lhs" | "scala" | "sc" | "py" => Some(FileCategory::Source),
+            "toml" | "json" | "yaml" | "yml" | "config" | "asmdef" => Some(FileCategory::Config),
+            "md" | "rst" | "txt" => Some(FileCategory::Doc),
+            "graphql" | "gql" | "proto" => Some(FileCategory::Schema),
+            _ => None,
+        }
+    }
+
+    fn categorize_file(&mut self, root: &Path, path: &Path) {
         if let Some(extension) = path.extension().and_then(|s| s.to_str()) {
-            match extension {
-                "rs" => {
-                    if path.to_string_lossy().contains("test") || 
-                       path.file_name().unwrap_or_default().to_string_lossy().starts_with("test_") {
-                        self.test_files.push(path.to_path_buf());
-                    } else {
-                        self.source_files.push(path.to_path_buf());
+            let category = self.file_categories.get(extension).copied().or_else(|| Self::builtin_category(extension));
+            match category {
+                Some(FileCategory::Source) => {
+                    // バイナリは内容を調べず、拡張子による分類のみ行う。非UTF-8のテキストは置換して読む
+                    let content = Self::read_code_file(path).filter(|bytes| !Self::is_binary(bytes));
+                    let text = content.as_deref().map(String::from_utf8_lossy);
+                    let relative = path.strip_prefix(root).unwrap_or(path);
+                    if !INCLUDE_GENERATED.load(Ordering::Relaxed) && Self::is_generated_file(relative, text.as_deref()) {
+                        trace(format!("生成ファイルとして別に数える: {}", path.display()));
+                        self.generated_files.push(path.to_path_buf());
+                        return;
                     }
-                },
-                "js" | "jsx" | "ts" | "tsx" => {
-                    if path.to_string_lossy().contains("test") || 
-                       path.to_string_lossy().contains("spec") ||
-                       path.file_name().unwrap_or_default().to_string_lossy().contains(".test.") {
+                    if let Some(bytes) = &content {
+                        self.add_lines(extension, bytes);
+                    }
+                    if Self::is_test_file(relative, extension, text.as_deref()) {
                         self.test_files.push(path.to_path_buf());
                     } else {
+                        if extension == "rs" && text.as_deref().is_some_and(|t| t.contains("#[cfg(test)]")) {
+                            self.inline_test_files.push(path.to_path_buf());
+                        }
+                        if extension == "rs" {
+                            match relative.components().next().and_then(|c| c.as_os_str().to_str()) {
+                                Some("examples") => self.example_files.push(path.to_path_buf()),
+                                Some("benches") => self.bench_files.push(path.to_path_buf()),
+                                _ => {}
+                            }
+                        }
                         self.source_files.push(path.to_path_buf());
                     }
                 },
-                "toml" | "json" | "yaml" | "yml" | "config" => {
+                Some(FileCategory::Config) => {
                     self.config_files.push(path.to_path_buf());
                 },
-                "md" | "rst" | "txt" => {
+                Some(FileCategory::Doc) => {
                     self.doc_files.push(path.to_path_buf());
                 },
-                _ => {}
+                Some(FileCategory::Schema) => {
+                    self.schema_files.push(path.to_path_buf());
+                },
+                None => {}
             }
         }
 
         // 特別なファイル名の処理
         if let Some(filename) = path.file_name().and_then(|s| s.to_str()) {
             match filename {
-                "Dockerfile" | ".dockerignore" | "docker-compose.yml" | "docker-compose.yaml" => {
+                "Dockerfile" | ".dockerignore" | "docker-compose.yml" | "docker-compose.yaml" | "Jenkinsfile" => {
                     self.config_files.push(path.to_path_buf());
                 },
                 "main.rs" | "lib.rs" | "index.js" | "index.ts" | "app.js" | "app.ts" => {
@@ -348,94 +2048,1213 @@ impl ProjectStructure {
         }
     }
 
-    fn should_skip_directory(&self, path: &Path) -> bool {
-        if let Some(dirname) = path.file_name().and_then(|s| s.to_str()) {
-            matches!(dirname, "target" | "node_modules" | ".git" | "dist" | "build" | ".next")
-        } else {
-            false
+    /// テストファイルか判定する。ディレクトリ・ファイル名の明確な規約を見た上で、内容のテスト記述を根拠にする
+    ///
+    /// `relative`はプロジェクトルートからの相対パス
+    fn is_test_file(relative: &Path, extension: &str, content: Option<&str>) -> bool {
+        let in_test_dir = relative.parent().is_some_and(|dir| {
+            dir.components().any(|c| c.as_os_str().to_str().is_some_and(|c| TEST_DIRECTORIES.contains(&c)))
+        });
+        let stem = relative.file_stem().unwrap_or_default().to_string_lossy();
+        let file_name = relative.file_name().unwrap_or_default().to_string_lossy();
+        let has_test_name = match extension {
+            "rs" => stem == "tests" || stem.starts_with("test_") || stem.ends_with("_test") || stem.ends_with("_tests"),
+            "cs" => stem.ends_with("Test") || stem.ends_with("Tests"),
+            "R" | "r" => stem.starts_with("test-") || stem.starts_with("test_"),
+            "hs" | "lhs" => stem.ends_with("Spec") || stem.ends_with("Test"),
+            "scala" | "sc" => stem.ends_with("Spec") || stem.ends_with("Test") || stem.ends_with("Suite"),
+            "py" => stem.starts_with("test_") || stem.ends_with("_test"),
+            _ => file_name.contains(".test.") || file_name.contains(".spec."),
+        };
+        if in_test_dir || has_test_name {
+            return true;
         }
-    }
 
-    pub fn extract_metadata(&mut self, project: &ProjectInfo) {
-        match project.project_type {
-            ProjectType::RustNormal | ProjectType::RustWasm => {
-                self.extract_rust_metadata(&project.path);
-            },
-            ProjectType::JavaScript | ProjectType::TypeScript | ProjectType::NodeJs => {
-                self.extract_js_metadata(&project.path);
+        let Some(content) = content else {
+            return false;
+        };
+        match extension {
+            // `#[cfg(test)]`のインラインテストを含むファイルはソースとして扱う
+            "rs" => {
+                (content.contains("#[test]") || content.contains("#[tokio::test]"))
+                    && !content.contains("#[cfg(test)]")
             },
+            "cs" => ["[Test]", "[UnityTest]", "[Fact]", "[TestMethod]"]
+                .iter()
+                .any(|attr| content.contains(attr)),
+            "R" | "r" => Self::contains_call(content, "test_that("),
+            "hs" | "lhs" => ["import Test.Hspec", "import Test.Tasty", "import Test.QuickCheck"]
+                .iter()
+                .any(|import| content.contains(import)),
+            "scala" | "sc" => ["import org.scalatest", "import munit", "import zio.test", "import weaver"]
+                .iter()
+                .any(|import| content.contains(import)),
+            "py" => ["import pytest", "import unittest", "from unittest import"]
+                .iter()
+                .any(|import| content.contains(import)),
+            _ => ["describe(", "it(", "test("]
+                .iter()
+                .any(|call| Self::contains_call(content, call)),
         }
     }
 
-    fn extract_rust_metadata(&mut self, path: &Path) {
-        let cargo_path = path.join("Cargo.toml");
-        if let Ok(content) = fs::read_to_string(&cargo_path) {
-            if let Ok(cargo_toml) = toml::from_str::<toml::Value>(&content) {
-                // 依存関係の抽出
-                if let Some(deps) = cargo_toml.get("dependencies") {
-                    if let Some(deps_table) = deps.as_table() {
-                        for (name, value) in deps_table {
-                            let version = match value {
-                                toml::Value::String(v) => v.clone(),
-                                toml::Value::Table(t) => {
-                                    t.get("version").and_then(|v| v.as_str()).unwrap_or("*").to_string()
-                                },
-                                _ => "*".to_string(),
-                            };
-                            self.dependencies.insert(name.clone(), version);
-                        }
-                    }
-                }
+    /// `call`が識別子やメソッド呼び出しの一部でない位置に現れるか（`submit(`や`regex.test(`を除外）
+    fn contains_call(content: &str, call: &str) -> bool {
+        content.match_indices(call).any(|(index, _)| {
+            content[..index]
+                .chars()
+                .next_back()
+                .is_none_or(|c| !(c.is_alphanumeric() || matches!(c, '_' | '$' | '.')))
+        })
+    }
 
-                // スクリプト（ビルドスクリプトなど）の抽出
-                if let Some(package) = cargo_toml.get("package") {
-                    if let Some(build) = package.get("build") {
-                        if let Some(build_script) = build.as_str() {
-                            self.scripts.insert("build".to_string(), build_script.to_string());
-                        }
+    /// 行数計測・テスト判定用にファイルを読み込む（サイズ上限を超えるファイルはNone）
+    fn read_code_file(path: &Path) -> Option<Vec<u8>> {
+        if fs::metadata(path).map(|m| m.len() > LOC_SIZE_LIMIT).unwrap_or(true) {
+            trace(format!("内容を読まない（サイズ上限超過）: {}", path.display()));
+            return None;
+        }
+        let bytes = fs::read(path).ok()?;
+        if Self::is_binary(&bytes) {
+            trace(format!("内容を読まない（バイナリ）: {}", path.display()));
+        }
+        Some(bytes)
+    }
+
+    /// 自動生成されたコードか（既知の接尾辞・ディレクトリ、または先頭付近の生成マーカー）
+    fn is_generated_file(relative: &Path, content: Option<&str>) -> bool {
+        let file_name = relative.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        let has_suffix = GENERATED_SUFFIXES.iter().any(|suffix| file_name.ends_with(suffix));
+        let in_generated_dir = relative.components().any(|c| {
+            matches!(c.as_os_str().to_str(), Some("generated" | "__generated__" | "gen-src"))
+        });
+        let has_marker = content.is_some_and(|text| {
+            text.lines().take(10).any(|line| {
+                GENERATED_MARKERS.iter().any(|marker| line.contains(marker))
+            })
+        });
+        has_suffix || in_generated_dir || has_marker
+    }
+
+    /// 先頭部分にNULバイトを含むものをバイナリとみなす
+    fn is_binary(bytes: &[u8]) -> bool {
+        bytes.iter().take(8192).any(|b| *b == 0)
+    }
+
+    /// ファイルの行数を拡張子毎に加算（サイズ上限を超えるファイル・バイナリは無視）
+    fn count_lines(&mut self, path: &Path, extension: &str) {
+        if let Some(bytes) = Self::read_code_file(path).filter(|bytes| !Self::is_binary(bytes)) {
+            self.add_lines(extension, &bytes);
+        }
+    }
+
+    fn add_lines(&mut self, extension: &str, bytes: &[u8]) {
+        let mut lines = bytes.iter().filter(|b| **b == b'\n').count();
+        if bytes.last().is_some_and(|b| *b != b'\n') {
+            lines += 1;
+        }
+        *self.loc_by_extension.entry(extension.to_string()).or_default() += lines;
+    }
+
+    /// テストファイルを置き場所毎にまとめる。件数の多い順で、各置き場所の代表例（名前順で最初のもの）を持つ
+    ///
+    /// 置き場所は`root`からの相対パスで、テスト用ディレクトリ配下ならそのディレクトリ、
+    /// それ以外（`*.test.ts`などソースと同じ場所）はファイルのあるディレクトリ
+    pub fn test_locations(&self, root: &Path) -> Vec<TestLocation> {
+        let mut locations: Vec<TestLocation> = Vec::new();
+        for file in &self.test_files {
+            let relative = file.strip_prefix(root).unwrap_or(file);
+            let parent = relative.parent().unwrap_or(Path::new(""));
+            let test_dir = parent
+                .ancestors()
+                .filter(|dir| dir.file_name().and_then(|n| n.to_str()).is_some_and(|n| TEST_DIRECTORIES.contains(&n)))
+                .last();
+            let (directory, dedicated) = match test_dir {
+                Some(dir) => (dir.to_path_buf(), true),
+                None => (parent.to_path_buf(), false),
+            };
+            match locations.iter_mut().find(|l| l.directory == directory) {
+                Some(location) => {
+                    location.count += 1;
+                    if relative < location.example.as_path() {
+                        location.example = relative.to_path_buf();
                     }
                 }
+                None => locations.push(TestLocation { directory, dedicated, count: 1, example: relative.to_path_buf() }),
             }
         }
+        locations.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.directory.cmp(&b.directory)));
+        locations
     }
 
-    fn extract_js_metadata(&mut self, path: &Path) {
-        let package_path = path.join("package.json");
-        if let Ok(content) = fs::read_to_string(&package_path) {
-            if let Ok(package_json) = serde_json::from_str::<serde_json::Value>(&content) {
-                // 依存関係の抽出
-                if let Some(deps) = package_json.get("dependencies") {
-                    if let Some(deps_obj) = deps.as_object() {
-                        for (name, value) in deps_obj {
-                            if let Some(version) = value.as_str() {
-                                self.dependencies.insert(name.clone(), version.to_string());
-                            }
-                        }
-                    }
+    /// ソース・テストファイル数から規模を判定する（閾値は設定ファイルの`project_size`）
+    pub fn size(&self, config: &ProjectSizeConfig) -> ProjectSize {
+        let files = self.source_files.len() + self.test_files.len();
+        if files >= config.large_min_files.unwrap_or(DEFAULT_LARGE_PROJECT_FILES) {
+            ProjectSize::Large
+        } else if files <= config.small_max_files.unwrap_or(DEFAULT_SMALL_PROJECT_FILES) {
+            ProjectSize::Small
+        } else {
+            ProjectSize::Medium
+        }
+    }
+
+    /// 最も行数の多い拡張子とその行数
+    pub fn dominant_language(&self) -> Option<(&str, usize)> {
+        self.loc_by_extension
+            .iter()
+            .max_by(|(a_ext, a), (b_ext, b)| a.cmp(b).then_with(|| b_ext.cmp(a_ext)))
+            .map(|(ext, lines)| (ext.as_str(), *lines))
+    }
+
+    /// 指定したパス集合に含まれるファイルのみを残す
+    pub fn retain_files(&mut self, paths: &HashSet<PathBuf>) {
+        let keep = |p: &PathBuf| fs::canonicalize(p).is_ok_and(|p| paths.contains(&p));
+        self.source_files.retain(keep);
+        self.test_files.retain(keep);
+        self.inline_test_files.retain(keep);
+        self.example_files.retain(keep);
+        self.bench_files.retain(keep);
+        self.generated_files.retain(keep);
+        self.config_files.retain(keep);
+        self.doc_files.retain(keep);
+        self.entry_points.retain(keep);
+        self.schema_files.retain(keep);
+        self.file_mtimes.retain(|p, _| keep(p));
+
+        // 行数は残ったソース・テストファイルから数え直す
+        self.loc_by_extension.clear();
+        let code_files: Vec<PathBuf> = self.source_files.iter().chain(&self.test_files).cloned().collect();
+        for path in code_files {
+            if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+                self.count_lines(&path, extension);
+            }
+        }
+        self.container_setup = self.detect_container_setup();
+    }
+
+    /// 指定したファイル群の中で最も新しく更新されたファイル
+    pub fn most_recently_modified<'a>(&self, files: &'a [PathBuf]) -> Option<(&'a PathBuf, SystemTime)> {
+        files
+            .iter()
+            .filter_map(|p| self.file_mtimes.get(p).map(|t| (p, *t)))
+            .max_by_key(|(_, t)| *t)
+    }
+
+    /// 指定したファイル群がしばらく更新されていないか
+    pub fn is_stale(&self, files: &[PathBuf]) -> bool {
+        self.most_recently_modified(files)
+            .and_then(|(_, t)| t.elapsed().ok())
+            .is_some_and(|elapsed| elapsed > STALE_THRESHOLD)
+    }
+
+    /// ドキュメントがソースの更新から大きく遅れているか
+    pub fn docs_are_stale(&self) -> bool {
+        let (Some((_, docs)), Some((_, sources))) = (
+            self.most_recently_modified(&self.doc_files),
+            self.most_recently_modified(&self.source_files),
+        ) else {
+            return false;
+        };
+        sources.duration_since(docs).is_ok_and(|lag| lag > STALE_THRESHOLD)
+    }
+
+    /// 依存関係からデータベースのORM・ドライバ名を検出
+    pub fn detect_database(&self) -> Option<String> {
+        const DATABASE_DEPENDENCIES: &[&str] = &[
+            // Rust
+            "diesel", "sqlx", "sea-orm", "rusqlite", "tokio-postgres", "mongodb",
+            // JavaScript/TypeScript
+            "prisma", "@prisma/client", "typeorm", "mongoose", "sequelize", "drizzle-orm", "knex", "pg", "mysql2",
+            // Python
+            "sqlalchemy", "SQLAlchemy", "django", "peewee",
+        ];
+
+        DATABASE_DEPENDENCIES
+            .iter()
+            .find(|dep| {
+                self.dependencies.contains_key(**dep)
+                    || self.dependencies.contains_key(&format!("dev:{}", dep))
+            })
+            .map(|dep| dep.to_string())
+    }
+
+    /// スキーマファイルなどから言語横断的な機能を検出
+    pub fn detect_cross_cutting_features(&self, root: &Path) -> Vec<String> {
+        let mut features = Vec::new();
+
+        let has_extension = |exts: &[&str]| {
+            self.schema_files.iter().any(|p| {
+                p.extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| exts.contains(&e))
+            })
+        };
+
+        if has_extension(&["graphql", "gql"]) {
+            features.push("graphql".to_string());
+        }
+
+        let has_proto_config = self.config_files.iter().any(|p| {
+            matches!(
+                p.file_name().and_then(|n| n.to_str()),
+                Some("buf.yaml" | "buf.gen.yaml" | "prototool.yaml")
+            )
+        });
+        if has_extension(&["proto"]) || has_proto_config {
+            features.push("grpc".to_string());
+        }
+
+        if let Some(database) = self.detect_database() {
+            features.push(format!("database:{}", database));
+        }
+
+        if self.detect_api_spec().is_some() {
+            features.push("openapi".to_string());
+        }
+
+        if self.detect_docs_site(root).is_some() {
+            features.push("docs-site".to_string());
+        }
+
+        for ci in self.detect_ci_systems() {
+            features.push(format!("ci:{}", ci.key()));
+        }
+
+        features.extend(self.container_setup.features().into_iter().map(String::from));
+
+        for tool in self.detect_lint_tools() {
+            features.push(format!("lint:{}", tool.key()));
+        }
+
+        features
+    }
+
+    /// Dockerfile・Compose・Dev Container・Kubernetesマニフェスト
+    pub fn container_setup(&self) -> &ContainerSetup {
+        &self.container_setup
+    }
+
+    /// Dockerfile・Compose・Dev Container・Kubernetesマニフェストを検出（Dockerfileは拡張子が無いため全ファイルから探す）
+    fn detect_container_setup(&self) -> ContainerSetup {
+        let mut paths: Vec<&PathBuf> = self.file_mtimes.keys().collect();
+        paths.sort();
+
+        let mut setup = ContainerSetup::default();
+        for path in paths {
+            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            let parent = path.parent().and_then(|p| p.file_name()).and_then(|n| n.to_str());
+            match file_name {
+                "Dockerfile" | "Containerfile" => setup.dockerfiles.push(path.clone()),
+                name if name.starts_with("Dockerfile.") || name.ends_with(".Dockerfile") => {
+                    setup.dockerfiles.push(path.clone());
                 }
+                "docker-compose.yml" | "docker-compose.yaml" | "compose.yml" | "compose.yaml" => {
+                    setup.compose_files.push(path.clone());
+                }
+                // docker-compose.override.yml・docker-compose.prod.yaml など
+                name if name.starts_with("docker-compose.") && (name.ends_with(".yml") || name.ends_with(".yaml")) => {
+                    setup.compose_files.push(path.clone());
+                }
+                "devcontainer.json" if parent == Some(".devcontainer") => setup.devcontainer = Some(path.clone()),
+                ".devcontainer.json" => setup.devcontainer = Some(path.clone()),
+                "kustomization.yaml" | "kustomization.yml" | "Chart.yaml" => setup.k8s_manifests.push(path.clone()),
+                _ => {}
+            }
+        }
 
-                // dev依存関係の抽出
-                if let Some(dev_deps) = package_json.get("devDependencies") {
-                    if let Some(dev_deps_obj) = dev_deps.as_object() {
-                        for (name, value) in dev_deps_obj {
-                            if let Some(version) = value.as_str() {
-                                self.dependencies.insert(format!("dev:{}", name), version.to_string());
-                            }
-                        }
+        setup.multi_stage = setup
+            .dockerfiles
+            .iter()
+            .filter(|path| {
+                let content = fs::read_to_string(path).unwrap_or_default();
+                content.lines().filter(|line| line.trim_start().to_ascii_uppercase().starts_with("FROM ")).count() > 1
+            })
+            .cloned()
+            .collect();
+
+        // Compose・CIの設定は`apiVersion:`を持たないため、内容で判定しても混ざらない
+        let mut yaml_files: Vec<&PathBuf> = self
+            .config_files
+            .iter()
+            .filter(|path| matches!(path.extension().and_then(|e| e.to_str()), Some("yaml" | "yml")))
+            .filter(|path| !setup.k8s_manifests.contains(path) && !setup.compose_files.contains(path))
+            .collect();
+        yaml_files.sort();
+        for path in yaml_files {
+            let content = fs::read_to_string(path).unwrap_or_default();
+            let has_key = |key: &str| content.lines().any(|line| line.starts_with(key));
+            if has_key("apiVersion:") && has_key("kind:") {
+                setup.k8s_manifests.push(path.clone());
+            }
+        }
+        setup.k8s_manifests.sort();
+        setup
+    }
+
+    /// 設定ファイルからドキュメントサイトジェネレータを検出
+    pub fn detect_docs_site(&self, root: &Path) -> Option<DocsSite> {
+        // docusaurus.config.js や .vitepress/config.mts は設定ファイルに分類されないため全ファイルから探す。
+        // サンプルや依存先の設定に反応しないよう、ルート直下か docs/ 直下のものだけを使う
+        let site_root = |dir: &Path| dir == Path::new("") || dir == Path::new("docs");
+        let mut paths: Vec<&PathBuf> = self.file_mtimes.keys().collect();
+        paths.sort();
+        paths
+            .into_iter()
+            .find_map(|path| {
+                let relative = path.strip_prefix(root).ok()?;
+                let file_name = relative.file_name().and_then(|n| n.to_str())?;
+                let dir = relative.parent()?;
+                match file_name {
+                    "book.toml" if site_root(dir) => Some(DocsSite::MdBook),
+                    name if name.starts_with("docusaurus.config.") && site_root(dir) => Some(DocsSite::Docusaurus),
+                    name if name.starts_with("config.")
+                        && dir.file_name().is_some_and(|n| n == ".vitepress")
+                        && dir.parent().is_some_and(site_root) =>
+                    {
+                        Some(DocsSite::VitePress)
                     }
+                    _ => None,
                 }
+            })
+    }
 
-                // スクリプトの抽出
-                if let Some(scripts) = package_json.get("scripts") {
-                    if let Some(scripts_obj) = scripts.as_object() {
-                        for (name, value) in scripts_obj {
-                            if let Some(script) = value.as_str() {
-                                self.scripts.insert(name.clone(), script.to_string());
-                            }
-                        }
+    /// 設定ファイルからリンター・フォーマッターを検出（`.eslintrc`などは設定ファイルに分類されないため全ファイルから探す）
+    pub fn detect_lint_tools(&self) -> Vec<LintTool> {
+        let mut paths: Vec<&PathBuf> = self.file_mtimes.keys().collect();
+        paths.sort();
+
+        let mut tools = Vec::new();
+        for path in paths {
+            if let Some(tool) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(LintTool::from_file_name)
+                && !tools.contains(&tool)
+            {
+                tools.push(tool);
+            }
+        }
+        tools
+    }
+
+    /// CI設定ファイルから利用しているCIシステムを検出
+    pub fn detect_ci_systems(&self) -> Vec<CiSystem> {
+        let mut systems = Vec::new();
+        for path in &self.config_files {
+            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            let parent = path.parent().and_then(|p| p.file_name()).and_then(|n| n.to_str());
+            let grandparent = path
+                .parent()
+                .and_then(|p| p.parent())
+                .and_then(|p| p.file_name())
+                .and_then(|n| n.to_str());
+
+            let system = match (file_name, parent, grandparent) {
+                (name, Some("workflows"), Some(".github")) if name.ends_with(".yml") || name.ends_with(".yaml") => {
+                    CiSystem::GitHubActions
+                },
+                (".gitlab-ci.yml", _, _) => CiSystem::GitLabCi,
+                ("config.yml", Some(".circleci"), _) => CiSystem::CircleCi,
+                ("azure-pipelines.yml" | "azure-pipelines.yaml", _, _) => CiSystem::AzurePipelines,
+                ("Jenkinsfile", _, _) => CiSystem::Jenkins,
+                _ => continue,
+            };
+            if !systems.contains(&system) {
+                systems.push(system);
+            }
+        }
+        systems
+    }
+
+    /// OpenAPI/Swagger仕様ファイルを検出し、タイトルとバージョンを読み取る
+    pub fn detect_api_spec(&self) -> Option<ApiSpec> {
+        let mut candidates: Vec<&PathBuf> = self
+            .config_files
+            .iter()
+            .filter(|p| {
+                matches!(
+                    p.file_name().and_then(|n| n.to_str()),
+                    Some("openapi.yaml" | "openapi.yml" | "openapi.json" | "swagger.yaml" | "swagger.yml" | "swagger.json")
+                )
+            })
+            .collect();
+        // ルートに近いファイルを優先
+        candidates.sort_by_key(|p| p.components().count());
+        let path = candidates.first()?;
+
+        let content = fs::read_to_string(path).unwrap_or_default();
+        let (title, version) = if path.extension().is_some_and(|e| e == "json") {
+            let spec = serde_json::from_str::<serde_json::Value>(&content).ok();
+            let info_field = |key: &str| {
+                spec.as_ref()
+                    .and_then(|s| s.get("info"))
+                    .and_then(|i| i.get(key))
+                    .and_then(|v| v.as_str())
+                    .map(|v| v.to_string())
+            };
+            (info_field("title"), info_field("version"))
+        } else {
+            Self::parse_yaml_spec_info(&content)
+        };
+
+        Some(ApiSpec {
+            path: path.to_path_buf(),
+            title,
+            version,
+        })
+    }
+
+    /// YAMLの`info:`ブロックから`title`と`version`を読み取る（簡易パーサ）
+    fn parse_yaml_spec_info(content: &str) -> (Option<String>, Option<String>) {
+        let mut title = None;
+        let mut version = None;
+        let mut in_info = false;
+
+        for line in content.lines() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            if !line.starts_with([' ', '\t']) {
+                in_info = line.trim_end() == "info:";
+                continue;
+            }
+            if !in_info {
+                continue;
+            }
+
+            let Some((key, value)) = line.trim().split_once(':') else {
+                continue;
+            };
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'').to_string();
+            if value.is_empty() {
+                continue;
+            }
+            match key {
+                "title" if title.is_none() => title = Some(value),
+                "version" if version.is_none() => version = Some(value),
+                _ => {}
+            }
+        }
+
+        (title, version)
+    }
+
+    /// `.ccmgen.toml`の`ignore_dirs`に該当するか（ディレクトリ名またはルートからの相対パスで比較）
+    fn is_ignored(&self, root: &Path, path: &Path) -> bool {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        self.ignore_dirs.iter().any(|ignored| {
+            let ignored = ignored.trim_end_matches('/');
+            relative == Path::new(ignored) || path.file_name().is_some_and(|n| n == ignored)
+        })
+    }
+
+    /// 生成物・依存ディレクトリや`ignore_dirs`の配下にあるパスか（`analyze --watch`の変更検知用）
+    pub fn is_excluded_path(&self, root: &Path, path: &Path) -> bool {
+        path.ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(root) && *dir != root)
+            .any(|dir| self.should_skip_directory(dir) || self.is_ignored(root, dir))
+    }
+
+    /// 監視するディレクトリ（ルートを含む。生成物・依存ディレクトリや`ignore_dirs`は辿らない）。`analyze --watch`用
+    pub fn watch_directories(&self, root: &Path) -> Vec<PathBuf> {
+        let mut directories = vec![root.to_path_buf()];
+        let mut pending = vec![root.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                // シンボリックリンクは辿らない
+                if entry.file_type().is_ok_and(|t| t.is_dir())
+                    && !self.should_skip_directory(&path)
+                    && !self.is_ignored(root, &path)
+                {
+                    directories.push(path.clone());
+                    pending.push(path);
+                }
+            }
+        }
+        directories
+    }
+
+    fn should_skip_directory(&self, path: &Path) -> bool {
+        if let Some(dirname) = path.file_name().and_then(|s| s.to_str()) {
+            // Library/Temp/Logs/obj はUnityが生成するディレクトリ（Unityプロジェクトのみ除外）
+            if self.unity && matches!(dirname, "Library" | "Temp" | "Logs" | "obj") {
+                return true;
+            }
+            // renv はRのパッケージライブラリ、.stack-work/dist-newstyle はStack/Cabalのビルドディレクトリ、.bsp/.metals/.bloop はScalaのツールの作業ディレクトリ、
+            // .venv/venv/__pycache__/.tox と *_cache はPythonの仮想環境・キャッシュ
+            matches!(dirname, "target" | "node_modules" | ".git" | "dist" | "build" | ".next"
+                | "renv" | ".stack-work" | "dist-newstyle"
+                | ".bsp" | ".metals" | ".bloop"
+                | ".venv" | "venv" | "__pycache__" | ".tox" | ".mypy_cache" | ".pytest_cache" | ".ruff_cache")
+        } else {
+            false
+        }
+    }
+
+    pub fn extract_metadata(&mut self, project: &ProjectInfo) {
+        match project.project_type {
+            ProjectType::RustNormal | ProjectType::RustWasm => {
+                trace("メタデータ抽出: Cargo.toml");
+                self.extract_rust_metadata(&project.path);
+            },
+            ProjectType::JavaScript | ProjectType::TypeScript | ProjectType::NodeJs | ProjectType::Bun => {
+                trace("メタデータ抽出: package.json");
+                self.extract_js_metadata(&project.path);
+            },
+            ProjectType::Unity => {
+                trace("メタデータ抽出: Packages/manifest.json");
+                self.extract_unity_metadata(&project.path);
+            },
+            ProjectType::R => {
+                trace("メタデータ抽出: DESCRIPTION / renv.lock");
+                self.extract_r_metadata(&project.path);
+            },
+            ProjectType::Haskell => {
+                trace("メタデータ抽出: package.yaml / *.cabal");
+                self.extract_haskell_metadata(&project.path);
+            },
+            ProjectType::Scala => {
+                trace("メタデータ抽出: build.sbt / build.sc");
+                self.extract_scala_metadata(&project.path);
+            },
+            ProjectType::Python => {
+                trace("メタデータ抽出: pyproject.toml / requirements.txt");
+                self.extract_python_metadata(&project.path);
+            },
+        }
+        trace(format!("依存関係 {}個, スクリプト {}個", self.dependencies.len(), self.scripts.len()));
+    }
+
+    fn extract_rust_metadata(&mut self, path: &Path) {
+        let cargo_path = path.join("Cargo.toml");
+        let Ok(content) = fs::read_to_string(&cargo_path) else {
+            return;
+        };
+        let Ok(cargo_toml) = toml::from_str::<toml::Value>(&content) else {
+            return;
+        };
+
+        // 依存関係の抽出
+        if let Some(deps_table) = cargo_toml.get("dependencies").and_then(|d| d.as_table()) {
+            let workspace_deps = Self::find_workspace_dependencies(path);
+            for (name, value) in deps_table {
+                let inherits_workspace = value
+                    .get("workspace")
+                    .and_then(|w| w.as_bool())
+                    .unwrap_or(false);
+                let version = if inherits_workspace {
+                    workspace_deps
+                        .as_ref()
+                        .and_then(|deps| deps.get(name))
+                        .map(Self::cargo_dependency_version)
+                        .unwrap_or_else(|| "*".to_string())
+                } else {
+                    Self::cargo_dependency_version(value)
+                };
+                self.dependencies.insert(name.clone(), version);
+            }
+        }
+
+        // スクリプト（ビルドスクリプトなど）の抽出
+        if let Some(build_script) = cargo_toml
+            .get("package")
+            .and_then(|p| p.get("build"))
+            .and_then(|b| b.as_str())
+        {
+            self.scripts.insert("build".to_string(), build_script.to_string());
+        }
+    }
+
+    fn cargo_dependency_version(value: &toml::Value) -> String {
+        match value {
+            toml::Value::String(v) => v.clone(),
+            toml::Value::Table(t) => {
+                t.get("version").and_then(|v| v.as_str()).unwrap_or("*").to_string()
+            },
+            _ => "*".to_string(),
+        }
+    }
+
+    /// 親ディレクトリを遡ってワークスペースルートの`[workspace.dependencies]`を取得
+    fn find_workspace_dependencies(path: &Path) -> Option<toml::Table> {
+        for dir in path.ancestors() {
+            let Ok(content) = fs::read_to_string(dir.join("Cargo.toml")) else {
+                continue;
+            };
+            let Ok(cargo_toml) = toml::from_str::<toml::Value>(&content) else {
+                continue;
+            };
+            if let Some(workspace) = cargo_toml.get("workspace") {
+                return workspace
+                    .get("dependencies")
+                    .and_then(|d| d.as_table())
+                    .cloned();
+            }
+        }
+        None
+    }
+
+    /// `Packages/manifest.json`からUnityパッケージを抽出
+    fn extract_unity_metadata(&mut self, path: &Path) {
+        let manifest_path = path.join("Packages").join("manifest.json");
+        let Ok(content) = fs::read_to_string(&manifest_path) else {
+            return;
+        };
+        let Ok(manifest) = serde_json::from_str::<serde_json::Value>(&content) else {
+            return;
+        };
+
+        if let Some(deps_obj) = manifest.get("dependencies").and_then(|d| d.as_object()) {
+            for (name, value) in deps_obj {
+                if let Some(version) = value.as_str() {
+                    self.dependencies.insert(name.clone(), version.to_string());
+                }
+            }
+        }
+    }
+
+    /// `DESCRIPTION`の`Depends`・`Imports`（無ければ`renv.lock`のパッケージ）を依存関係として抽出
+    fn extract_r_metadata(&mut self, path: &Path) {
+        if let Some(fields) = ProjectDetector::read_description_file(path) {
+            for key in ["Depends", "Imports"] {
+                let Some(value) = fields.get(key) else {
+                    continue;
+                };
+                for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                    // `dplyr (>= 1.0.0)` → ("dplyr", ">= 1.0.0")
+                    let (name, version) = match entry.split_once('(') {
+                        Some((name, constraint)) => (name.trim(), constraint.trim_end_matches(')').trim()),
+                        None => (entry, "*"),
+                    };
+                    // `Depends: R (>= 4.0)`はRのバージョン指定なので除く
+                    if name != "R" {
+                        self.dependencies.insert(name.to_string(), version.to_string());
                     }
                 }
             }
+            return;
+        }
+
+        let Ok(content) = fs::read_to_string(path.join("renv.lock")) else {
+            return;
+        };
+        let Ok(lock) = serde_json::from_str::<serde_json::Value>(&content) else {
+            return;
+        };
+        if let Some(packages) = lock.get("Packages").and_then(|p| p.as_object()) {
+            for (name, package) in packages {
+                let version = package.get("Version").and_then(|v| v.as_str()).unwrap_or("*");
+                self.dependencies.insert(name.clone(), version.to_string());
+            }
+        }
+    }
+
+    /// `package.yaml`の`dependencies`、無ければ`*.cabal`の`build-depends`を依存関係として抽出
+    fn extract_haskell_metadata(&mut self, path: &Path) {
+        let entries: Vec<String> = if let Ok(content) = fs::read_to_string(path.join("package.yaml")) {
+            Self::hpack_dependencies(&content)
+        } else if let Some(content) = ProjectDetector::find_cabal_file(path).and_then(|f| fs::read_to_string(f).ok()) {
+            Self::cabal_build_depends(&content)
+        } else {
+            return;
+        };
+
+        for entry in entries {
+            // `aeson >=2.0 && <2.3` → ("aeson", ">=2.0 && <2.3")
+            let (name, version) = match entry.split_once(|c: char| c.is_whitespace() || "<>=^".contains(c)) {
+                Some((name, _)) => (name, entry[name.len()..].trim()),
+                None => (entry.as_str(), ""),
+            };
+            // 同じパッケージが複数のコンポーネントに現れる場合はバージョン制約付きの方を残す
+            if version.is_empty() {
+                self.dependencies.entry(name.to_string()).or_insert_with(|| "*".to_string());
+            } else {
+                self.dependencies.insert(name.to_string(), version.to_string());
+            }
+        }
+    }
+
+    /// `package.yaml`の`dependencies:`（トップレベル・各セクション内）の`- name >= x`を集める
+    fn hpack_dependencies(content: &str) -> Vec<String> {
+        let mut entries = Vec::new();
+        let mut in_dependencies: Option<usize> = None;
+        for line in content.lines() {
+            let indent = line.len() - line.trim_start().len();
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            // YAMLではリスト項目がキーと同じインデントでも良い
+            if let Some(base) = in_dependencies {
+                if let Some(item) = trimmed.strip_prefix("- ")
+                    && indent >= base
+                {
+                    entries.push(item.trim().to_string());
+                    continue;
+                }
+                if indent <= base {
+                    in_dependencies = None;
+                }
+            }
+            if let Some(rest) = trimmed.strip_prefix("dependencies:") {
+                // `dependencies: base` や `dependencies: [base, text]` の1行形式
+                let rest = rest.trim().trim_start_matches('[').trim_end_matches(']');
+                entries.extend(rest.split(',').map(str::trim).filter(|e| !e.is_empty()).map(String::from));
+                in_dependencies = Some(indent);
+            }
+        }
+        entries
+    }
+
+    /// `*.cabal`の各コンポーネントの`build-depends:`（継続行を含む）をカンマで分けて集める
+    fn cabal_build_depends(content: &str) -> Vec<String> {
+        let mut entries = Vec::new();
+        let mut field_indent: Option<usize> = None;
+        for line in content.lines() {
+            let indent = line.len() - line.trim_start().len();
+            let trimmed = line.trim();
+            if trimmed.starts_with("--") {
+                continue;
+            }
+            let value = match (field_indent, trimmed.split_once(':')) {
+                (_, Some((key, value))) if key.trim().eq_ignore_ascii_case("build-depends") => {
+                    field_indent = Some(indent);
+                    value
+                }
+                (Some(base), _) if indent > base && !trimmed.is_empty() => trimmed,
+                _ => {
+                    field_indent = None;
+                    continue;
+                }
+            };
+            entries.extend(value.split(',').map(str::trim).filter(|e| !e.is_empty()).map(String::from));
+        }
+        entries
+    }
+
+    /// `build.sbt`の`"org" %% "name" % "version"`、`build.sc`の`ivy"org::name:version"`を依存関係として抽出
+    ///
+    /// バージョンが変数（`% catsVersion`）の場合は`*`とする
+    fn extract_scala_metadata(&mut self, path: &Path) {
+        if let Ok(content) = fs::read_to_string(path.join("build.sbt")) {
+            for (name, version) in Self::sbt_dependencies(&content) {
+                self.dependencies.insert(name, version);
+            }
+        } else if let Ok(content) = fs::read_to_string(path.join("build.sc")) {
+            for coordinate in content.split("ivy\"").skip(1).filter_map(|rest| rest.split('"').next()) {
+                let parts: Vec<&str> = coordinate.split(':').filter(|p| !p.is_empty()).collect();
+                if let [_, name, rest @ ..] = parts.as_slice() {
+                    let version = rest.first().copied().unwrap_or("*");
+                    self.dependencies.insert(name.to_string(), version.to_string());
+                }
+            }
+        }
+    }
+
+    /// `pyproject.toml`の`[project]`・`[tool.poetry]`から依存関係・スクリプトを抽出（無ければ`requirements.txt`）
+    fn extract_python_metadata(&mut self, path: &Path) {
+        let pyproject = ProjectDetector::read_pyproject(path).ok();
+        let project = pyproject.as_ref().and_then(|p| p.get("project"));
+        let poetry = pyproject.as_ref().and_then(|p| p.get("tool")).and_then(|t| t.get("poetry"));
+
+        let requirements = project.and_then(|p| p.get("dependencies")).and_then(|d| d.as_array());
+        for requirement in requirements.into_iter().flatten().filter_map(|r| r.as_str()) {
+            if let Some((name, version)) = Self::parse_python_requirement(requirement) {
+                self.dependencies.insert(name, version);
+            }
+        }
+        if let Some(deps) = poetry.and_then(|p| p.get("dependencies")).and_then(|d| d.as_table()) {
+            // `python = "^3.11"`は対応するPythonのバージョンなので依存関係に含めない
+            for (name, value) in deps.iter().filter(|(name, _)| *name != "python") {
+                self.dependencies.insert(name.clone(), Self::cargo_dependency_version(value));
+            }
+        }
+        if self.dependencies.is_empty()
+            && let Ok(content) = fs::read_to_string(path.join("requirements.txt"))
+        {
+            for line in content.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with(['#', '-'])) {
+                if let Some((name, version)) = Self::parse_python_requirement(line) {
+                    self.dependencies.insert(name, version);
+                }
+            }
+        }
+
+        for scripts in [project.and_then(|p| p.get("scripts")), poetry.and_then(|p| p.get("scripts"))] {
+            for (name, target) in scripts.and_then(|s| s.as_table()).into_iter().flatten() {
+                if let Some(target) = target.as_str() {
+                    self.scripts.insert(name.clone(), target.to_string());
+                }
+            }
+        }
+    }
+
+    /// PEP 508の依存指定（`requests[socks]>=2.31; python_version < "3.12"`）から名前とバージョン指定を取り出す
+    fn parse_python_requirement(requirement: &str) -> Option<(String, String)> {
+        let requirement = requirement.split(';').next().unwrap_or(requirement).trim();
+        let end = requirement
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.')))
+            .unwrap_or(requirement.len());
+        let (name, rest) = requirement.split_at(end);
+        if name.is_empty() {
+            return None;
+        }
+        // extras（`[socks]`）を除く
+        let rest = match rest.trim_start().strip_prefix('[') {
+            Some(rest) => rest.split_once(']').map_or("", |(_, rest)| rest),
+            None => rest,
+        };
+        let version = rest.trim();
+        let version = if version.is_empty() { "*" } else { version };
+        Some((name.to_string(), version.to_string()))
+    }
+
+    /// `build.sbt`から`"org" %% "name" % "version"`の並びを探す
+    fn sbt_dependencies(content: &str) -> Vec<(String, String)> {
+        #[derive(PartialEq)]
+        enum Token {
+            Str(String),
+            Percent,
+            Other,
+        }
+
+        let mut tokens = Vec::new();
+        let mut chars = content.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => tokens.push(Token::Str(chars.by_ref().take_while(|c| *c != '"').collect())),
+                '%' => {
+                    while chars.next_if_eq(&'%').is_some() {}
+                    tokens.push(Token::Percent);
+                }
+                c if c.is_whitespace() => {}
+                c if c.is_alphanumeric() || c == '_' => {
+                    while chars.next_if(|c| c.is_alphanumeric() || *c == '_' || *c == '.').is_some() {}
+                    tokens.push(Token::Other);
+                }
+                _ => tokens.push(Token::Other),
+            }
+        }
+
+        let mut dependencies = Vec::new();
+        for index in 0..tokens.len().saturating_sub(2) {
+            // 直前が`%`なら`"name" % "version"`の途中なので数えない
+            if index > 0 && tokens[index - 1] == Token::Percent {
+                continue;
+            }
+            if let (Token::Str(_), Token::Percent, Token::Str(name)) = (&tokens[index], &tokens[index + 1], &tokens[index + 2]) {
+                let version = match (tokens.get(index + 3), tokens.get(index + 4)) {
+                    (Some(Token::Percent), Some(Token::Str(version))) => version.clone(),
+                    _ => "*".to_string(),
+                };
+                dependencies.push((name.clone(), version));
+            }
+        }
+        dependencies
+    }
+
+    fn extract_js_metadata(&mut self, path: &Path) {
+        let package_path = path.join("package.json");
+        let Ok(content) = fs::read_to_string(&package_path) else {
+            return;
+        };
+        let Ok(package_json) = serde_json::from_str::<serde_json::Value>(&content) else {
+            return;
+        };
+
+        // 依存関係の抽出
+        if let Some(deps_obj) = package_json.get("dependencies").and_then(|d| d.as_object()) {
+            for (name, value) in deps_obj {
+                if let Some(version) = value.as_str() {
+                    self.dependencies.insert(name.clone(), version.to_string());
+                }
+            }
         }
+
+        // dev依存関係の抽出
+        if let Some(dev_deps_obj) = package_json.get("devDependencies").and_then(|d| d.as_object()) {
+            for (name, value) in dev_deps_obj {
+                if let Some(version) = value.as_str() {
+                    self.dependencies.insert(format!("dev:{}", name), version.to_string());
+                }
+            }
+        }
+
+        // スクリプトの抽出
+        if let Some(scripts_obj) = package_json.get("scripts").and_then(|s| s.as_object()) {
+            for (name, value) in scripts_obj {
+                if let Some(script) = value.as_str() {
+                    self.scripts.insert(name.clone(), script.to_string());
+                }
+            }
+        }
+    }
+}
+
+/// ソースが依存関係を参照しているか
+/// （`serde::`・`extern crate`・`from 'react'`・`require("lodash/fp")`・`import numpy`・`library(dplyr)`などの形）
+fn references_dependency(content: &str, name: &str) -> bool {
+    let module = name.replace('-', "_");
+    let is_ident_char = |c: char| c.is_alphanumeric() || c == '_';
+
+    let quoted = ['"', '\''].iter().any(|q| content.contains(&format!("{q}{name}{q}")) || content.contains(&format!("{q}{name}/")));
+    let path = format!("{module}::");
+    let qualified = content
+        .match_indices(&path)
+        .any(|(i, _)| !content[..i].ends_with(is_ident_char));
+    quoted || qualified || content.lines().any(|line| {
+        ["import ", "from ", "extern crate ", "library(", "require("].iter().any(|keyword| {
+            line.trim_start()
+                .strip_prefix(keyword)
+                .and_then(|rest| rest.trim_start().strip_prefix(module.as_str()))
+                .is_some_and(|rest| !rest.starts_with(is_ident_char))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO_TOML: &str = "[package]\nname = \"fixture\"\nversion = \"0.1.0\"\nedition = \"2021\"\n";
+
+    /// 実際の`~/.claude/ccmgen.toml`やgitの設定を読まないよう、HOMEを空の一時ディレクトリにする
+    fn isolate_home() {
+        static HOME: std::sync::OnceLock<tempfile::TempDir> = std::sync::OnceLock::new();
+        HOME.get_or_init(|| {
+            let home = tempfile::tempdir().expect("一時ディレクトリを作成できません");
+            // SAFETY: HOMEを読むテストはすべてfixture経由でこの初期化の完了を待つ
+            unsafe { std::env::set_var("HOME", home.path()) };
+            home
+        });
+    }
+
+    /// (ルートからの相対パス, 内容)のファイルを持つ一時ディレクトリ
+    fn fixture(files: &[(&str, &[u8])]) -> tempfile::TempDir {
+        isolate_home();
+        let dir = tempfile::tempdir().expect("一時ディレクトリを作成できません");
+        for (relative, content) in files {
+            let path = dir.path().join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    fn context(dir: &tempfile::TempDir) -> ProjectContext {
+        ProjectDetector::create_project_context(dir.path()).expect("プロジェクトを検出できません")
+    }
+
+    /// ルートからの相対パス（区切りは`/`）
+    fn names(paths: &[PathBuf], dir: &tempfile::TempDir) -> Vec<String> {
+        let mut names: Vec<String> = paths
+            .iter()
+            .map(|p| p.strip_prefix(dir.path()).unwrap().to_string_lossy().replace('\\', "/"))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn rust_files_with_test_in_the_name_are_sources_unless_they_follow_conventions() {
+        let dir = fixture(&[
+            ("Cargo.toml", CARGO_TOML.as_bytes()),
+            ("src/main.rs", b"fn main() {}\n"),
+            ("src/latest_features.rs", b"pub fn latest() {}\n"),
+            ("src/contest.rs", b"pub fn contest() {}\n"),
+            ("src/parser_test.rs", b"fn helper() {}\n"),
+            ("tests/integration.rs", b"fn check() {}\n"),
+            ("src/checks.rs", b"#[test]\nfn works() {}\n"),
+            ("src/lib.rs", b"pub fn f() {}\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn t() {}\n}\n"),
+        ]);
+        let structure = context(&dir).structure;
+
+        assert_eq!(
+            names(&structure.test_files, &dir),
+            ["src/checks.rs", "src/parser_test.rs", "tests/integration.rs"]
+        );
+        assert_eq!(
+            names(&structure.source_files, &dir),
+            ["src/contest.rs", "src/latest_features.rs", "src/lib.rs", "src/main.rs"]
+        );
+        assert_eq!(names(&structure.inline_test_files, &dir), ["src/lib.rs"]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn javascript_tests_are_detected_by_test_calls_not_method_calls() {
+        let dir = fixture(&[
+            ("package.json", br#"{"name": "fixture"}"#),
+            ("src/form.js", b"form.submit(); /^a/.test(value);\n"),
+            ("src/latest.js", b"export const latest = 1;\n"),
+            ("src/button.js", b"describe('button', () => { it('renders', () => {}); });\n"),
+            ("src/util.spec.js", b"export {};\n"),
+        ]);
+        let structure = context(&dir).structure;
+
+        assert_eq!(names(&structure.test_files, &dir), ["src/button.js", "src/util.spec.js"]);
+        assert_eq!(names(&structure.source_files, &dir), ["src/form.js", "src/latest.js"]);
+    }
+
+    #[test]
+    fn is_test_file_prefers_naming_conventions_over_substrings() {
+        let is_test = |path: &str, content: Option<&str>| {
+            let path = Path::new(path);
+            let extension = path.extension().unwrap().to_str().unwrap();
+            ProjectStructure::is_test_file(path, extension, content)
+        };
+        assert!(!is_test("src/latest_features.rs", None));
+        assert!(!is_test("src/attestation.py", None));
+        assert!(is_test("src/test_parser.py", None));
+        assert!(is_test("tests/conftest.py", None));
+        assert!(is_test("src/parser.py", Some("import pytest\n")));
+        assert!(!is_test("src/lib.rs", Some("#[cfg(test)]\nmod tests {\n    #[test]\n    fn t() {}\n}\n")));
+        assert!(is_test("Assets/Scripts/PlayerTests.cs", None));
+    }
+
+    #[test]
+    fn github_workflows_directory_suggests_ci_enhancement() {
+        let dir = fixture(&[
+            ("Cargo.toml", CARGO_TOML.as_bytes()),
+            ("src/main.rs", b"fn main() {}\n"),
+            (".github/workflows/ci.yml", b"on: push\njobs: {}\n"),
+        ]);
+        let context = context(&dir);
+
+        assert!(context.structure.directories.contains(".github"));
+        assert!(context.suggested_commands.iter().any(|c| c == "ci-cd-enhancement"));
+    }
+
+    #[test]
+    fn key_directories_are_recorded_only_at_the_root() {
+        let dir = fixture(&[
+            ("Cargo.toml", CARGO_TOML.as_bytes()),
+            ("src/main.rs", b"fn main() {}\n"),
+            (".circleci/config.yml", b"version: 2.1\n"),
+            ("examples/demo.rs", b"fn main() {}\n"),
+            ("src/docs/notes.md", b"# Notes\n"),
+        ]);
+        let context = context(&dir);
+
+        let mut directories: Vec<&str> = context.structure.directories.iter().map(String::as_str).collect();
+        directories.sort();
+        assert_eq!(directories, [".circleci", "examples"]);
+        assert!(context.suggested_commands.iter().any(|c| c == "ci-cd-enhancement"));
+    }
+
+    #[test]
+    fn ci_enhancement_is_not_suggested_without_ci_configuration() {
+        let dir = fixture(&[("Cargo.toml", CARGO_TOML.as_bytes()), ("src/main.rs", b"fn main() {}\n")]);
+        let context = context(&dir);
+
+        assert!(context.structure.directories.is_empty());
+        assert!(!context.suggested_commands.iter().any(|c| c == "ci-cd-enhancement"));
+    }
+
+    #[test]
+    fn non_utf8_source_is_read_lossily() {
+        let dir = fixture(&[
+            ("Cargo.toml", CARGO_TOML.as_bytes()),
+            ("src/main.rs", b"fn main() {}\n"),
+            // Latin-1の"caf\xe9"を含む不正なUTF-8
+            ("src/legacy.rs", b"// caf\xe9\n#[test]\nfn works() {}\n"),
+        ]);
+        let structure = context(&dir).structure;
+
+        assert_eq!(names(&structure.test_files, &dir), ["src/legacy.rs"]);
+        assert_eq!(structure.loc_by_extension.get("rs"), Some(&4));
+    }
+
+    #[test]
+    fn binary_source_is_categorized_by_extension_without_reading_content() {
+        let dir = fixture(&[
+            ("Cargo.toml", CARGO_TOML.as_bytes()),
+            ("src/main.rs", b"fn main() {}\n"),
+            ("src/blob.rs", b"\0\x01\x02#[test]\nfn looks_like_a_test() {}\n"),
+        ]);
+        let structure = context(&dir).structure;
+
+        assert_eq!(names(&structure.source_files, &dir), ["src/blob.rs", "src/main.rs"]);
+        assert!(structure.test_files.is_empty());
+        assert_eq!(structure.loc_by_extension.get("rs"), Some(&1));
+    }
+
+    #[test]
+    fn unity_build_directories_are_skipped_only_in_unity_projects() {
+        let dir = fixture(&[
+            ("Cargo.toml", CARGO_TOML.as_bytes()),
+            ("src/main.rs", b"fn main() {}\n"),
+            ("src/obj/mod.rs", b"pub fn load() {}\n"),
+            ("src/Library/mod.rs", b"pub fn shelf() {}\n"),
+        ]);
+        let structure = context(&dir).structure;
+        assert_eq!(
+            names(&structure.source_files, &dir),
+            ["src/Library/mod.rs", "src/main.rs", "src/obj/mod.rs"]
+        );
+
+        let dir = fixture(&[
+            ("ProjectSettings/ProjectVersion.txt", b"m_EditorVersion: 2022.3.0f1\n"),
+            ("Assets/Scripts/Player.cs", b"class Player {}\n"),
+            ("Library/ScriptAssemblies/Cache.cs", b"class Cache {}\n"),
+            ("obj/Debug/Generated.cs", b"class Generated {}\n"),
+        ]);
+        let structure = context(&dir).structure;
+        assert_eq!(names(&structure.source_files, &dir), ["Assets/Scripts/Player.cs"]);
+    }
+
+    #[test]
+    fn malformed_pyproject_is_still_detected_as_python() {
+        let dir = fixture(&[
+            ("pyproject.toml", b"[project\nname = \"broken\"\n"),
+            ("src/app.py", b"print('hi')\n"),
+        ]);
+        let info = ProjectDetector::detect_project(dir.path()).expect("プロジェクトを検出できません");
+
+        assert_eq!(info.project_type, ProjectType::Python);
+        assert_eq!(info.name, dir.path().canonicalize().unwrap().file_name().unwrap().to_string_lossy());
+    }
+
+    #[test]
+    fn is_binary_checks_for_nul_bytes() {
+        assert!(ProjectStructure::is_binary(b"abc\0def"));
+        assert!(!ProjectStructure::is_binary("caf\u{e9}".as_bytes()));
+        assert!(!ProjectStructure::is_binary(b"caf\xe9"));
+    }
+
+    #[test]
+    fn javascript_refinements_do_not_lower_each_others_confidence() {
+        let dir = fixture(&[
+            (
+                "package.json",
+                br#"{"name": "api", "dependencies": {"express": "^4"}, "devDependencies": {"typescript": "^5"}}"#,
+            ),
+            ("tsconfig.json", b"{}"),
+        ]);
+        let candidates = ProjectDetector::score_candidates(dir.path());
+
+        assert_eq!(candidates[0].project_type, ProjectType::TypeScript);
+        assert!(candidates[0].confidence >= LOW_CONFIDENCE_THRESHOLD);
+    }
+
+    #[test]
+    fn wit_directory_alone_does_not_make_a_wasm_project() {
+        let plain = fixture(&[
+            ("Cargo.toml", CARGO_TOML.as_bytes()),
+            ("src/main.rs", b"fn main() {}\n"),
+            ("wit/world.wit", b"package example:app;\n"),
+        ]);
+        assert_eq!(context(&plain).info.project_type, ProjectType::RustNormal);
+
+        let component = fixture(&[
+            ("Cargo.toml", format!("{}\n[dependencies]\nwit-bindgen = \"0.41\"\n", CARGO_TOML).as_bytes()),
+            ("src/lib.rs", b"pub fn f() {}\n"),
+            ("wit/world.wit", b"package example:app;\n"),
+        ]);
+        assert_eq!(context(&component).info.project_type, ProjectType::RustWasm);
+    }
+}
@@ -1,14 +1,150 @@
-use crate::project::{ProjectContext, ProjectType};
 use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::config::{parse_frontmatter, ConfigManager, ConflictPolicy, TemplateMetadata};
+use crate::error::CcmgenError;
+use crate::project::{Bundler, CiSystem, ContainerSetup, DocsSite, LintTool, MetaFramework, ProjectContext, ProjectType};
 
 pub struct SmartTemplateManager;
 
+/// テンプレートの出どころ（読み込む順）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TemplateSource {
+    Builtin,
+    Suggested,
+    Custom,
+    Provider,
+    Project,
+}
+
+impl TemplateSource {
+    fn label(&self) -> &'static str {
+        match self {
+            TemplateSource::Builtin => "組み込み",
+            TemplateSource::Suggested => "プロジェクト固有の提案",
+            TemplateSource::Custom => "カスタムテンプレート",
+            TemplateSource::Provider => "template_provider",
+            TemplateSource::Project => ".ccmgen.toml",
+        }
+    }
+
+    fn is_builtin(&self) -> bool {
+        matches!(self, TemplateSource::Builtin | TemplateSource::Suggested)
+    }
+}
+
+/// 出どころ毎に集めたテンプレート。同名のものは`conflict_policy`に従って1つにし、衝突を報告する
+pub struct TemplateSet {
+    templates: Vec<(String, String, TemplateSource)>,
+    policy: ConflictPolicy,
+    /// `conflict_policy = "error"`で衝突した名前
+    conflicts: Vec<String>,
+}
+
+impl TemplateSet {
+    pub fn new(policy: ConflictPolicy) -> Self {
+        Self { templates: Vec::new(), policy, conflicts: Vec::new() }
+    }
+
+    /// `source`のテンプレートを追加する。同じ出どころ内の同名は後のもの、組み込みと同名の提案は提案を採用する
+    pub fn add(&mut self, source: TemplateSource, templates: impl IntoIterator<Item = (String, String)>) {
+        for (name, content) in templates {
+            let Some(existing) = self.templates.iter_mut().find(|(n, _, _)| *n == name) else {
+                self.templates.push((name, content, source));
+                continue;
+            };
+            // 同名の提案は組み込みテンプレートをプロジェクトに合わせたものなので黙って置き換える
+            if existing.2 == source || (existing.2 == TemplateSource::Builtin && source == TemplateSource::Suggested) {
+                existing.1 = content;
+                existing.2 = source;
+                continue;
+            }
+
+            let keep_existing = match self.policy {
+                ConflictPolicy::CustomWins => false,
+                ConflictPolicy::BuiltinWins => existing.2.is_builtin() && !source.is_builtin(),
+                ConflictPolicy::Error => {
+                    if !self.conflicts.contains(&name) {
+                        self.conflicts.push(name.clone());
+                    }
+                    eprintln!("❌ {}: {} と {} で同じ名前のテンプレートが定義されています", name, existing.2.label(), source.label());
+                    continue;
+                }
+            };
+            let winner = if keep_existing { existing.2 } else { source };
+            eprintln!(
+                "⚠️ {}: {} と {} で同じ名前のテンプレートが定義されています → {} を使用します",
+                name,
+                existing.2.label(),
+                source.label(),
+                winner.label()
+            );
+            if !keep_existing {
+                existing.1 = content;
+                existing.2 = source;
+            }
+        }
+    }
+
+    /// 追加した順のテンプレート。`conflict_policy = "error"`で衝突があればエラー
+    pub fn into_templates(self) -> Result<Vec<(String, String)>, CcmgenError> {
+        if !self.conflicts.is_empty() {
+            return Err(CcmgenError::TemplateConflict(self.conflicts));
+        }
+        Ok(self.templates.into_iter().map(|(name, content, _)| (name, content)).collect())
+    }
+}
+
+/// `template_provider`が出力するテンプレート1件
+#[derive(Deserialize)]
+struct ProvidedTemplate {
+    name: String,
+    body: String,
+}
+
+/// `template_provider`の終了を待つ最大時間（超えたら終了させて無視する）
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// `max_context_chars`未指定時のコンテキストの最大文字数
+const DEFAULT_MAX_CONTEXT_CHARS: usize = 800;
+
+/// `review-architecture`に列挙するディレクトリ数
+const MODULE_LAYOUT_LIMIT: usize = 10;
+
+/// コンテキストを切り詰めた場合に末尾に付ける印
+const TRUNCATED_MARKER: &str = "(truncated)";
+
+/// `init --no-context`でプロジェクト情報を付けずにテンプレートをそのまま生成するか
+static NO_CONTEXT: AtomicBool = AtomicBool::new(false);
+
+/// プロジェクト情報の付加・変数の展開をせず、テンプレートの本文をそのまま生成する
+pub fn disable_context() {
+    NO_CONTEXT.store(true, Ordering::Relaxed);
+}
+
+/// `init --template-vars key=value`で渡された変数
+static TEMPLATE_VARS: OnceLock<Vec<(String, String)>> = OnceLock::new();
+
+/// テンプレートの描画時にプロジェクト由来の変数に加えて使う変数を設定する（同名ならこちらを優先）
+pub fn set_template_vars(vars: Vec<(String, String)>) {
+    let _ = TEMPLATE_VARS.set(vars);
+}
+
 impl SmartTemplateManager {
-    pub fn generate_context_specific_templates(context: &ProjectContext) -> Vec<(String, String)> {
+    /// 提案されたコマンドのテンプレートを作る。`base_context`は`build_context_string`の結果
+    pub fn generate_context_specific_templates(context: &ProjectContext, base_context: &str) -> Vec<(String, String)> {
         let mut templates = Vec::new();
         
         for suggested_cmd in &context.suggested_commands {
-            if let Some((name, content)) = Self::create_template_for_command(suggested_cmd, context) {
+            if let Some((name, content)) = Self::create_template_for_command(suggested_cmd, context, base_context) {
                 templates.push((name, content));
             }
         }
@@ -16,14 +152,31 @@ impl SmartTemplateManager {
         templates
     }
 
-    fn create_template_for_command(command: &str, context: &ProjectContext) -> Option<(String, String)> {
-        let base_context = Self::build_context_string(context);
-        
+    /// `generate_context_specific_templates`が作るテンプレートの名前（提案のうちテンプレートを持つもの）
+    pub fn suggested_template_names(context: &ProjectContext) -> Vec<String> {
+        Self::generate_context_specific_templates(context, "")
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    fn create_template_for_command(command: &str, context: &ProjectContext, base_context: &str) -> Option<(String, String)> {
         match command {
             "run-specific-test" => Some((
                 command.to_string(),
-                format!("{}\n\nRun a specific test file or test function in this Rust project. Please specify the test to run:", base_context)
+                format!("{}\n\n{}\n\nRun a specific test file or test function in this Rust project, following the test layout above. Please specify the test to run:", base_context, Self::build_test_layout(context))
+            )),
+            "add-example" => Some((
+                command.to_string(),
+                format!("{}\n\n{}\n\nAdd a new example under examples/ that demonstrates this functionality, following the conventions of the existing examples above. Use only the crate's public API, keep it runnable with `cargo run --example <name>`, and explain what it shows in a doc comment at the top:", base_context, Self::build_file_listing("Examples", &context.structure.example_files, context))
             )),
+            "add-benchmark" => {
+                let harness = Self::bench_harness(&context.structure.bench_files);
+                Some((
+                    command.to_string(),
+                    format!("{}\n\n{}\nBenchmark harness: {}\n\nAdd a benchmark under benches/ for this code using the same harness and structure as the existing benchmarks above (register it with a [[bench]] entry in Cargo.toml if they do), measuring realistic input sizes and keeping setup out of the timed section:", base_context, Self::build_file_listing("Benchmarks", &context.structure.bench_files, context), harness)
+                ))
+            },
             "async-refactor" => Some((
                 command.to_string(),
                 format!("{}\n\nRefactor this synchronous Rust code to use async/await patterns, considering the tokio/async-std dependencies:", base_context)
@@ -60,89 +213,893 @@ impl SmartTemplateManager {
                 command.to_string(),
                 format!("{}\n\nCreate Express.js route handlers with proper error handling and validation:", base_context)
             )),
+            "bun-route-generator" => Some((
+                command.to_string(),
+                format!("{}\n\nCreate route handlers for this Bun HTTP server with proper error handling and validation:", base_context)
+            )),
+            "add-playmode-test" => Some((
+                command.to_string(),
+                format!("{}\n\nCreate a Play Mode test for this Unity MonoBehaviour using the Unity Test Framework ([UnityTest] coroutines and an assembly definition for the tests):", base_context)
+            )),
             "database-model-generator" => Some((
                 command.to_string(),
                 format!("{}\n\nGenerate database models and schemas for this Node.js application:", base_context)
             )),
-            "documentation-generator" => Some((
+            "add-next-route-handler" => Some((
                 command.to_string(),
-                format!("{}\n\nGenerate comprehensive documentation for this project including README, API docs, and code comments:", base_context)
+                format!("{}\n\nCreate a Next.js App Router route handler (route.ts) for this functionality, with proper request validation and typed responses:", base_context)
+            )),
+            "add-nuxt-composable" => Some((
+                command.to_string(),
+                format!("{}\n\nExtract this logic into a Nuxt composable (composables/) using auto-imports and useFetch/useState where appropriate:", base_context)
+            )),
+            "generate-astro-component" => Some((
+                command.to_string(),
+                format!("{}\n\nGenerate an Astro component for this functionality, keeping it server-rendered and adding client islands only where interactivity is required:", base_context)
+            )),
+            "add-sveltekit-route" => Some((
+                command.to_string(),
+                format!("{}\n\nCreate a SvelteKit route (+page.svelte with +page.ts/+page.server.ts load function) for this functionality:", base_context)
+            )),
+            "generate-wit-bindings" => Some((
+                command.to_string(),
+                format!("{}\n\nDefine a WIT interface (world, interfaces, records and resources under wit/) for this functionality and generate the Rust guest bindings with wit-bindgen (or cargo component), implementing the generated Guest trait:", base_context)
+            )),
+            "add-component-export" => Some((
+                command.to_string(),
+                format!("{}\n\nExport this functionality from the WebAssembly component: add the function or interface to the world's exports in the WIT file, implement it on the component type, and register it with export!:", base_context)
+            )),
+            "review-wasi-imports" => Some((
+                command.to_string(),
+                format!("{}\n\nReview the WASI imports this code depends on (filesystem, clocks, random, sockets, environment): keep the set of required capabilities minimal, handle missing preopens and denied capabilities gracefully, and note any WASI preview 1 APIs that should move to preview 2 interfaces:", base_context)
+            )),
+            "review-interrupt-safety" => Some((
+                command.to_string(),
+                format!("{}\n\nReview this embedded Rust code for interrupt safety: state shared with interrupt handlers must go through critical sections (critical_section::Mutex, RTIC/Embassy primitives) or atomics, handlers should be short and never block, and there must be no data races on static mut:", base_context)
+            )),
+            "optimize-flash-usage" => Some((
+                command.to_string(),
+                format!("{}\n\nReduce the flash (and RAM) footprint of this no_std firmware: look for formatting machinery pulled in by panics and core::fmt (consider defmt and panic-halt), monomorphization bloat, large constant tables that could move to flash, and release profile settings (opt-level = \"s\"/\"z\", lto, codegen-units = 1):", base_context)
+            )),
+            "add-hal-abstraction" => Some((
+                command.to_string(),
+                format!("{}\n\nRefactor this driver to depend on embedded-hal traits (digital::OutputPin, i2c::I2c, spi::SpiDevice, delay::DelayNs) instead of a concrete chip HAL, so it can be reused across microcontrollers and unit-tested with embedded-hal-mock:", base_context)
             )),
-            "docker-optimization" => Some((
+            "generate-trybuild-test" => Some((
                 command.to_string(),
-                format!("{}\n\nOptimize the Dockerfile and Docker configuration for this project:", base_context)
+                format!("{}\n\nGenerate trybuild tests for this procedural macro: passing cases under tests/ui/pass and failing cases under tests/ui/fail with .stderr snapshots checking the compile error messages:", base_context)
             )),
-            "ci-cd-enhancement" => Some((
+            "review-span-handling" => Some((
                 command.to_string(),
-                format!("{}\n\nImprove CI/CD pipeline configuration for this project:", base_context)
+                format!("{}\n\nReview the span handling in this procedural macro: generated tokens should carry the spans of the input they came from, errors should be reported with syn::Error::new_spanned / to_compile_error instead of panics, and hygiene (Span::call_site vs mixed_site) should be intentional:", base_context)
             )),
+            "add-derive-attribute" => Some((
+                command.to_string(),
+                format!("{}\n\nAdd a helper attribute to this derive macro (declared in #[proc_macro_derive(..., attributes(...))]), parsing its arguments with syn and reporting unknown or malformed options as spanned compile errors:", base_context)
+            )),
+            "add-route-loader" => {
+                let instructions = match context.info.meta_framework {
+                    Some(MetaFramework::SvelteKit) => "Add a SvelteKit load function for this route (+page.server.ts for server-only data, +page.ts for universal data), returning typed data consumed via the page's data prop:",
+                    Some(MetaFramework::Remix) => "Add a Remix loader for this route module in app/routes, returning typed data with json() and reading it in the component with useLoaderData:",
+                    Some(MetaFramework::Qwik) => "Add a Qwik City routeLoader$ for this route in src/routes, exporting it from the route module and reading it in the component via the returned hook:",
+                    _ => "Add a data loader for this route that fetches its data on the server before rendering:",
+                };
+                Some((command.to_string(), format!("{}\n\n{}", base_context, instructions)))
+            },
+            "generate-form-action" => {
+                let instructions = match context.info.meta_framework {
+                    Some(MetaFramework::SvelteKit) => "Generate SvelteKit form actions (export const actions in +page.server.ts) for this form, with server-side validation, fail() for errors and progressive enhancement via use:enhance:",
+                    Some(MetaFramework::Remix) => "Generate a Remix action for this route module to handle the form submission, with server-side validation, returning errors for useActionData and redirecting on success:",
+                    Some(MetaFramework::Qwik) => "Generate a Qwik City routeAction$ for this form, with zod$ validation and the <Form> component for progressive enhancement:",
+                    _ => "Generate a server-side handler for this form submission with validation and error reporting:",
+                };
+                Some((command.to_string(), format!("{}\n\n{}", base_context, instructions)))
+            },
+            "review-data-fetching" => {
+                let framework = match context.info.meta_framework {
+                    Some(MetaFramework::SvelteKit) => "SvelteKit load functions",
+                    Some(MetaFramework::Remix) => "Remix loaders and actions",
+                    Some(MetaFramework::Qwik) => "Qwik City routeLoader$/routeAction$",
+                    _ => "route loaders",
+                };
+                Some((
+                    command.to_string(),
+                    format!("{}\n\nReview the data fetching in these routes ({}): look for request waterfalls between parent and child routes, data fetched on the client that belongs in the loader, missing error handling, and over-fetching:", base_context, framework)
+                ))
+            },
+            "bundle-analysis" => {
+                let instructions = match context.info.bundler {
+                    Some(Bundler::Vite) => "Optimize the Vite build for this project: tune build.rollupOptions.output.manualChunks, dynamic imports, and dependency pre-bundling (optimizeDeps) to reduce bundle size:",
+                    Some(Bundler::Webpack) => "Optimize the webpack build for this project: tune optimization.splitChunks, lazy-loaded chunks, tree shaking, and loader configuration to reduce bundle size:",
+                    Some(Bundler::Rollup) => "Optimize the Rollup build for this project: tune output.manualChunks, plugin ordering, tree shaking, and external dependencies to reduce bundle size:",
+                    Some(Bundler::Esbuild) => "Optimize the esbuild build for this project: tune splitting, minification, external packages, and target settings to reduce bundle size:",
+                    None => "Analyze this JavaScript code for bundle size optimization opportunities:",
+                };
+                Some((command.to_string(), format!("{}\n\n{}", base_context, instructions)))
+            },
+            "generate-resolvers" => Some((
+                command.to_string(),
+                format!("{}\n\nGenerate GraphQL resolvers for the following schema types, matching the project's existing resolver structure:", base_context)
+            )),
+            "add-dataloader" => Some((
+                command.to_string(),
+                format!("{}\n\nAdd DataLoader-based batching and caching to these GraphQL resolvers:", base_context)
+            )),
+            "review-n-plus-one" => Some((
+                command.to_string(),
+                format!("{}\n\nReview these GraphQL resolvers for N+1 query problems and suggest fixes:", base_context)
+            )),
+            "generate-client-from-spec" => Some((
+                command.to_string(),
+                format!("{}\n\nGenerate a typed API client for this project from the OpenAPI spec{}, covering every operation and its request/response schemas:", base_context, Self::api_spec_reference(context))
+            )),
+            "add-endpoint-to-spec" => Some((
+                command.to_string(),
+                format!("{}\n\nAdd the following endpoint to the OpenAPI spec{}, reusing existing components/schemas and keeping naming consistent:", base_context, Self::api_spec_reference(context))
+            )),
+            "validate-against-spec" => Some((
+                command.to_string(),
+                format!("{}\n\nCheck that this handler's routes, parameters, and response shapes match the OpenAPI spec{}, and list any mismatches:", base_context, Self::api_spec_reference(context))
+            )),
+            "add-doc-page" => Some((
+                command.to_string(),
+                format!("{}\n\nWrite a new {} documentation page on the following topic, matching the front matter, heading structure, and tone of the existing pages:", base_context, Self::docs_site_name(context))
+            )),
+            "review-doc-style" => Some((
+                command.to_string(),
+                format!("{}\n\nReview this {} documentation page for clarity, consistent terminology, broken or missing cross-links, and code examples that no longer match the project:", base_context, Self::docs_site_name(context))
+            )),
+            "generate-sidebar" => {
+                let instructions = match context.structure.detect_docs_site(&context.info.path) {
+                    Some(DocsSite::MdBook) => "Generate or update SUMMARY.md for this mdBook so that every page under src/ is listed in a logical reading order:",
+                    Some(DocsSite::Docusaurus) => "Generate or update sidebars.js for this Docusaurus site so that every doc is grouped into logical categories:",
+                    Some(DocsSite::VitePress) => "Generate or update the themeConfig.sidebar in .vitepress/config for this VitePress site so that every page is grouped into logical sections:",
+                    None => "Generate a sidebar/table of contents for this documentation site:",
+                };
+                Some((command.to_string(), format!("{}\n\n{}", base_context, instructions)))
+            },
+            "generate-grpc-service-impl" => Some((
+                command.to_string(),
+                format!("{}\n\nImplement the gRPC service defined in the following .proto file using this project's language and gRPC framework:", base_context)
+            )),
+            "add-interceptor" => Some((
+                command.to_string(),
+                format!("{}\n\nAdd a gRPC interceptor (e.g. for logging, authentication, or metrics) to this service:", base_context)
+            )),
+            "review-proto-compat" => Some((
+                command.to_string(),
+                format!("{}\n\nReview these .proto changes for backward/forward compatibility issues (field numbers, reserved fields, type changes):", base_context)
+            )),
+            "review-sql-injection" => Some((
+                command.to_string(),
+                format!("{}\n\nReview this database access code for SQL injection and unsafe query construction, and suggest parameterized alternatives:", base_context)
+            )),
+            "generate-migration" => Some((
+                command.to_string(),
+                format!("{}\n\nGenerate a database migration (with up and down steps) for the following schema change, using this project's ORM/migration tool:", base_context)
+            )),
+            "optimize-query" => Some((
+                command.to_string(),
+                format!("{}\n\nAnalyze and optimize the following database query (indexes, N+1 access, unnecessary columns or round trips):", base_context)
+            )),
+            "readme-generator" => Some((
+                command.to_string(),
+                format!("{}\n\nGenerate a README.md for this project covering what it does, how to install and run it, and a short usage example:", base_context)
+            )),
+            "review-architecture" => Some((
+                command.to_string(),
+                format!("{}\n\n{}\n\nReview the architecture of this large codebase: identify module boundaries that leak, circular or overly broad dependencies between the areas above, and duplicated responsibilities, and propose incremental refactorings:", base_context, Self::build_module_layout(context))
+            )),
+            "documentation-generator" => Some((
+                command.to_string(),
+                format!("{}\n\nGenerate comprehensive documentation for this project including README, API docs, and code comments:", base_context)
+            )),
+            "docker-optimization" => {
+                let setup = context.structure.container_setup();
+                let stages = if setup.multi_stage.is_empty() {
+                    "convert them to multi-stage builds so compilers and build-only dependencies stay out of the runtime image"
+                } else {
+                    "keep build-only dependencies out of the final stage of the multi-stage builds and share cached stages between them"
+                };
+                Some((
+                    command.to_string(),
+                    format!(
+                        "{}\n\n{}\n\nOptimize the Dockerfiles listed above: order layers for cache reuse, use smaller pinned base images, cover build artifacts in .dockerignore, run as a non-root user, and {}. Keep the changes consistent with the other container configuration listed:",
+                        base_context,
+                        Self::container_tooling(setup, context),
+                        stages
+                    )
+                ))
+            },
+            "optimize-compose" => {
+                let setup = context.structure.container_setup();
+                Some((
+                    command.to_string(),
+                    format!(
+                        "{}\n\n{}\n\nReview and optimize the Docker Compose configuration listed above: depends_on with healthcheck conditions, named volumes and networks, environment variables and secrets kept out of the file, build contexts that reuse the project's Dockerfiles, and override files or profiles that separate development from production:",
+                        base_context,
+                        Self::container_tooling(setup, context)
+                    )
+                ))
+            },
+            "review-k8s-manifests" => {
+                let setup = context.structure.container_setup();
+                Some((
+                    command.to_string(),
+                    format!(
+                        "{}\n\n{}\n\nReview the Kubernetes manifests listed above for resource requests and limits, liveness/readiness probes, securityContext (non-root, read-only root filesystem, dropped capabilities), image tags pinned instead of `latest`, consistent labels and selectors, and ConfigMap/Secret usage. Report the issues per file with a corrected snippet:",
+                        base_context,
+                        Self::container_tooling(setup, context)
+                    )
+                ))
+            },
+            "add-devcontainer" => {
+                let setup = context.structure.container_setup();
+                let base = if !setup.compose_files.is_empty() {
+                    "attaches to the application service of the existing Docker Compose configuration (dockerComposeFile/service)"
+                } else {
+                    "builds from the existing Dockerfile (build.dockerfile) so development matches the deployed image"
+                };
+                Some((
+                    command.to_string(),
+                    format!(
+                        "{}\n\n{}\n\nCreate .devcontainer/devcontainer.json for this {:?} project that {}, installs the toolchain and editor extensions the project needs, forwards the ports the application listens on, and runs the dependency install step as postCreateCommand:",
+                        base_context,
+                        Self::container_tooling(setup, context),
+                        context.info.project_type,
+                        base
+                    )
+                ))
+            },
+            "fix-type-errors" => {
+                let has = |tool: &str| context.info.features.iter().any(|f| f == tool);
+                let run = Self::python_runner(context);
+                let checkers: Vec<String> = ["mypy", "pyright"]
+                    .into_iter()
+                    .filter(|tool| has(tool))
+                    .map(|tool| format!("{} (`{}{} .`)", tool, run, tool))
+                    .collect();
+                Some((
+                    command.to_string(),
+                    format!(
+                        "{}\n\nFix the type errors reported by {} in the following Python code, respecting the strictness settings configured for the project (pyproject.toml [tool.*] or the tool's own config file). Prefer precise annotations, Protocols, TypedDicts and narrowing over `Any`, `cast` or `# type: ignore`:",
+                        base_context,
+                        checkers.join(" and ")
+                    )
+                ))
+            },
+            "fix-python-style" => {
+                let has = |tool: &str| context.info.features.iter().any(|f| f == tool);
+                let run = Self::python_runner(context);
+                let mut instructions = Vec::new();
+                if has("ruff") {
+                    instructions.push(format!("fix Ruff violations (`{}ruff check --fix`) according to the project's Ruff configuration", run));
+                }
+                if has("black") {
+                    instructions.push(format!("format with Black (`{}black .`) using the project's [tool.black] settings", run));
+                } else if has("ruff") {
+                    instructions.push(format!("format with `{}ruff format`", run));
+                }
+                Some((
+                    command.to_string(),
+                    format!(
+                        "{}\n\nClean up the following Python code: {}. Fix the underlying issues rather than adding `noqa` comments or ignoring rules:",
+                        base_context,
+                        instructions.join("; ")
+                    )
+                ))
+            },
+            "fix-lint-violations" => {
+                let tools = context.structure.detect_lint_tools();
+                let instructions: Vec<&str> = tools
+                    .iter()
+                    .map(|tool| match tool {
+                        LintTool::Rustfmt => "format with rustfmt using the project's rustfmt.toml",
+                        LintTool::Clippy => "fix clippy warnings (cargo clippy --all-targets) respecting clippy.toml",
+                        LintTool::Eslint => "fix ESLint errors according to the project's ESLint config",
+                        LintTool::Prettier => "format with Prettier using the project's .prettierrc",
+                        LintTool::Biome => "fix Biome lint and format issues according to biome.json",
+                        LintTool::Ruff => "fix Ruff violations according to ruff.toml",
+                        LintTool::EditorConfig => "follow the indentation and line-ending rules in .editorconfig",
+                    })
+                    .collect();
+                Some((
+                    command.to_string(),
+                    format!(
+                        "{}\n\nFix the lint and formatting violations in the following code: {}. Fix the underlying issues rather than disabling rules or adding ignore comments:",
+                        base_context,
+                        instructions.join("; ")
+                    )
+                ))
+            },
+            "ci-cd-enhancement" => {
+                let systems = context.structure.detect_ci_systems();
+                let instructions = match systems.first() {
+                    Some(CiSystem::GitHubActions) => "Improve the GitHub Actions workflows in .github/workflows for this project: add dependency caching, matrix builds, concurrency groups to cancel superseded runs, and least-privilege `permissions`:",
+                    Some(CiSystem::GitLabCi) => "Improve the GitLab CI pipeline in .gitlab-ci.yml for this project: organize stages, add cache and artifacts configuration, use `rules` to skip unnecessary jobs, and parallelize where possible:",
+                    Some(CiSystem::CircleCi) => "Improve the CircleCI configuration in .circleci/config.yml for this project: use orbs, add dependency caching, split tests across parallel containers, and define reusable executors and commands:",
+                    Some(CiSystem::AzurePipelines) => "Improve the Azure Pipelines configuration in azure-pipelines.yml for this project: split it into stages and templates, add pipeline caching, and use variable groups for secrets:",
+                    Some(CiSystem::Jenkins) => "Improve the Jenkinsfile for this project: use declarative pipeline syntax, run stages in parallel, add post conditions for cleanup and notifications, and stash build artifacts:",
+                    None => "Improve CI/CD pipeline configuration for this project:",
+                };
+                Some((command.to_string(), format!("{}\n\n{}", base_context, instructions)))
+            },
             _ => None,
         }
     }
 
-    fn build_context_string(context: &ProjectContext) -> String {
+    /// テンプレートの先頭に付けるプロジェクトの概要（`max_chars`文字まで）
+    fn build_context_string(context: &ProjectContext, max_chars: usize) -> String {
         let info = &context.info;
         let structure = &context.structure;
-        
-        let mut ctx = format!("Project: {} ({:?})", info.name, info.project_type);
+
+        // (優先度, 行)。上限を超える場合は優先度の低い行から省く（0が最優先）
+        let mut lines: Vec<(u8, String)> = vec![(0, format!("Project: {} ({:?})", info.name, info.project_type))];
+
+        if let Some(description) = &info.description {
+            lines.push((1, format!("Description: {}", description)));
+        }
+
+        if let Some(framework) = &info.meta_framework {
+            lines.push((1, format!("Framework: {:?}", framework)));
+        }
+
+        if let Some(bundler) = &info.bundler {
+            lines.push((2, format!("Bundler: {:?}", bundler)));
+        }
         
         if !info.features.is_empty() {
-            ctx.push_str(&format!("\nFeatures: {}", info.features.join(", ")));
+            lines.push((1, format!("Features: {}", info.features.join(", "))));
+        }
+
+        if let Some(edition) = &info.rust_edition {
+            lines.push((1, format!("Rust edition: {} (use idioms valid for this edition)", edition)));
+        }
+
+        if let Some(msrv) = &info.rust_version {
+            lines.push((1, format!("MSRV: Rust {} (avoid language features and std APIs stabilized after this version)", msrv)));
+        }
+
+        if let Some(license) = structure.detect_license() {
+            lines.push((3, format!("License: {}", license)));
+        }
+
+        if !structure.readme_topics.is_empty() {
+            lines.push((3, format!("Topics: {}", structure.readme_topics.join(", "))));
         }
         
-        ctx.push_str(&format!("\nFiles: {} source, {} tests, {} configs", 
+        lines.push((2, format!("Files: {} source, {} tests, {} configs", 
             structure.source_files.len(), 
             structure.test_files.len(), 
-            structure.config_files.len()));
+            structure.config_files.len())));
+
+        if let Some(git) = &context.git {
+            let branch = git.branch.as_deref().unwrap_or("(detached)");
+            let state = if git.dirty { "uncommitted changes" } else { "clean" };
+            lines.push((2, format!("Git: branch {}, {}", branch, state)));
+            if let Some(remote) = &git.remote_url {
+                lines.push((3, format!("Remote: {}", remote)));
+            }
+            if let Some(commit) = &git.last_commit {
+                lines.push((3, format!("Last commit: {}", commit)));
+            }
+        }
+
+        if let Some(spec) = structure.detect_api_spec() {
+            let title = spec.title.as_deref().unwrap_or("untitled");
+            match &spec.version {
+                Some(version) => lines.push((2, format!("API spec: {} v{}", title, version))),
+                None => lines.push((2, format!("API spec: {}", title))),
+            }
+        }
+
+        if let Some((extension, lines_of_code)) = structure.dominant_language() {
+            lines.push((3, format!("Lines of code: {} (.{})", lines_of_code, extension)));
+        }
         
+        // HashMapの順序は実行毎に変わるため、再実行で内容が変わらないよう名前順にする
         if !structure.dependencies.is_empty() {
-            let key_deps: Vec<_> = structure.dependencies.keys()
+            let mut key_deps: Vec<_> = structure.dependencies.keys()
                 .filter(|k| Self::is_important_dependency(k, &info.project_type))
-                .take(5)
                 .collect();
+            key_deps.sort();
+            key_deps.truncate(5);
             if !key_deps.is_empty() {
                 let deps_str: Vec<String> = key_deps.iter().map(|s| s.to_string()).collect();
-                ctx.push_str(&format!("\nKey dependencies: {}", deps_str.join(", ")));
+                lines.push((0, format!("Key dependencies: {}", deps_str.join(", "))));
             }
         }
         
         if !structure.scripts.is_empty() {
-            let scripts: Vec<_> = structure.scripts.keys().take(3).collect();
+            let mut scripts: Vec<_> = structure.scripts.keys().collect();
+            scripts.sort();
+            scripts.truncate(3);
             let scripts_str: Vec<String> = scripts.iter().map(|s| s.to_string()).collect();
-            ctx.push_str(&format!("\nAvailable scripts: {}", scripts_str.join(", ")));
+            lines.push((4, format!("Available scripts: {}", scripts_str.join(", "))));
         }
         
-        ctx
+        Self::truncate_context(lines, max_chars)
+    }
+
+    /// 上限の文字数に収まるまで優先度の低い行（同じ優先度なら後ろの行）から省き、省いた場合は印を付ける
+    fn truncate_context(mut lines: Vec<(u8, String)>, max_chars: usize) -> String {
+        let join = |lines: &[(u8, String)]| lines.iter().map(|(_, line)| line.as_str()).collect::<Vec<_>>().join("\n");
+        let mut ctx = join(&lines);
+        if max_chars == 0 || ctx.chars().count() <= max_chars {
+            return ctx;
+        }
+
+        let budget = max_chars.saturating_sub(TRUNCATED_MARKER.len() + 1);
+        while lines.len() > 1 && ctx.chars().count() > budget {
+            let lowest = lines.iter().map(|(priority, _)| *priority).max().unwrap_or(0);
+            if let Some(index) = lines.iter().rposition(|(priority, _)| *priority == lowest) {
+                lines.remove(index);
+            }
+            ctx = join(&lines);
+        }
+        // 最優先の行だけでも収まらない場合は文字単位で切り詰める
+        if ctx.chars().count() > budget {
+            ctx = ctx.chars().take(budget).collect();
+        }
+        format!("{}\n{}", ctx, TRUNCATED_MARKER)
+    }
+
+    fn docs_site_name(context: &ProjectContext) -> &'static str {
+        match context.structure.detect_docs_site(&context.info.path) {
+            Some(DocsSite::MdBook) => "mdBook",
+            Some(DocsSite::Docusaurus) => "Docusaurus",
+            Some(DocsSite::VitePress) => "VitePress",
+            None => "documentation site",
+        }
+    }
+
+    /// プロンプト中で参照する仕様ファイルのパス（例: ` (docs/openapi.yaml)`）
+    fn api_spec_reference(context: &ProjectContext) -> String {
+        context
+            .structure
+            .detect_api_spec()
+            .map(|spec| {
+                let path = spec.path.strip_prefix(&context.info.path).unwrap_or(&spec.path);
+                format!(" ({})", path.display())
+            })
+            .unwrap_or_default()
     }
 
     fn is_important_dependency(dep_name: &str, project_type: &ProjectType) -> bool {
         match project_type {
             ProjectType::RustNormal | ProjectType::RustWasm => {
-                matches!(dep_name, "tokio" | "async-std" | "serde" | "clap" | "wasm-bindgen" | "web-sys" | "js-sys")
+                matches!(dep_name, "tokio" | "async-std" | "serde" | "clap" | "wasm-bindgen" | "web-sys" | "js-sys"
+                    | "syn" | "quote" | "proc-macro2" | "embedded-hal" | "cortex-m" | "cortex-m-rt" | "embassy-executor"
+                    | "wit-bindgen" | "wasmtime")
             },
             ProjectType::JavaScript | ProjectType::TypeScript | ProjectType::NodeJs => {
                 matches!(dep_name, "react" | "vue" | "express" | "fastify" | "mongoose" | "prisma" | "jest" | "typescript")
             },
+            ProjectType::Bun => {
+                matches!(dep_name, "elysia" | "hono" | "react" | "prisma" | "drizzle-orm" | "typescript")
+            },
+            ProjectType::Unity => {
+                matches!(dep_name, "com.unity.inputsystem" | "com.unity.render-pipelines.universal"
+                    | "com.unity.render-pipelines.high-definition" | "com.unity.addressables" | "com.unity.entities")
+            },
+            ProjectType::R => {
+                matches!(dep_name, "dplyr" | "ggplot2" | "data.table" | "tidyr" | "shiny" | "Rcpp" | "testthat")
+            },
+            ProjectType::Haskell => {
+                matches!(dep_name, "aeson" | "text" | "containers" | "mtl" | "lens" | "servant" | "hspec")
+            },
+            ProjectType::Python => {
+                matches!(dep_name, "django" | "flask" | "fastapi" | "pydantic" | "sqlalchemy" | "numpy" | "pandas" | "requests" | "httpx")
+            },
+            ProjectType::Scala => {
+                ["cats-core", "cats-effect", "zio", "akka-actor", "pekko-actor", "http4s-", "circe-", "scalatest"]
+                    .iter()
+                    .any(|prefix| dep_name.starts_with(prefix))
+            },
         }
     }
 
-    pub fn create_enhanced_init_templates(context: &ProjectContext) -> Vec<(String, String)> {
-        let mut templates = Vec::new();
+    /// 組み込み・プロジェクト固有の提案・カスタム・`template_provider`・`.ccmgen.toml`のテンプレートをこの順に集める
+    ///
+    /// 同名のテンプレートは`conflict_policy`に従って1つにする（既定では後のものを採用して警告）
+    pub fn create_enhanced_init_templates(context: &ProjectContext) -> Result<Vec<(String, String)>, CcmgenError> {
+        if NO_CONTEXT.load(Ordering::Relaxed) {
+            return Self::create_raw_init_templates(context);
+        }
+
+        let config = ConfigManager::load_config();
+        let mut templates = TemplateSet::new(config.conflict_policy);
+        let template_context = Self::build_template_context(context);
+        // 設定の読み込み・ライセンスの判定はテンプレート毎ではなく1回だけ
+        let base_context = Self::build_context_string(context, config.max_context_chars.unwrap_or(DEFAULT_MAX_CONTEXT_CHARS));
         
         // 既存の基本テンプレートを取得
         let base_templates = crate::templates::TemplateManager::get_templates_for_project(&context.info);
         
         // 基本テンプレートをプロジェクトコンテキストで拡張
-        for (name, content) in base_templates {
+        templates.add(TemplateSource::Builtin, base_templates.into_iter().map(|(name, content)| {
             let enhanced_content = format!("{}\n\n{}", 
-                Self::build_context_string(context), 
-                content);
-            templates.push((name.to_string(), enhanced_content));
-        }
+                base_context, 
+                Self::render_template(name, content, &template_context));
+            (name.to_string(), enhanced_content)
+        }));
         
         // プロジェクト固有のテンプレートを追加
-        templates.extend(Self::generate_context_specific_templates(context));
+        templates.add(TemplateSource::Suggested, Self::generate_context_specific_templates(context, &base_context));
+
+        let project_type = format!("{:?}", context.info.project_type);
+        let custom_templates = Self::render_custom_templates(context.info.project_type.language_key())
+            .into_iter()
+            .filter(|(_, project_type_filter, _)| {
+                project_type_filter.as_ref().is_none_or(|t| *t == project_type)
+            })
+            .map(|(name, _, content)| {
+                let rendered = Self::render_template(&name, &content, &template_context);
+                (name, Self::with_context(&base_context, &rendered))
+            });
+        templates.add(TemplateSource::Custom, custom_templates);
+
+        if let Some(provider) = config.template_provider {
+            templates.add(TemplateSource::Provider, Self::run_template_provider(&provider, context));
+        }
+
+        // リポジトリ側の設定のため環境変数は展開しない（秘密情報がコマンドファイルに書き出されないように）
+        if let Some(project_config) = &context.project_config {
+            let project_templates = project_config.templates.iter().map(|t| {
+                let rendered = Self::render_template(&t.name, &t.content, &template_context);
+                (t.name.clone(), format!("{}\n\n{}", base_context, rendered))
+            });
+            templates.add(TemplateSource::Project, project_templates);
+        }
         
+        templates.into_templates()
+    }
+
+    /// `template_provider`を実行し、出力されたテンプレートを返す。失敗した場合は警告して空を返す
+    ///
+    /// 本文はプロバイダがプロジェクト情報から組み立てる前提のため、コンテキストの付加・変数の展開はしない
+    fn run_template_provider(provider: &str, context: &ProjectContext) -> Vec<(String, String)> {
+        let input = match serde_json::to_vec(context) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("⚠️ プロジェクト情報をJSONに変換できませんでした: {}", e);
+                return Vec::new();
+            }
+        };
+
+        // プロジェクトのディレクトリで実行するため、相対パスは設定ファイルのディレクトリを基準にする（名前だけならPATHから探す）
+        let program = match ConfigManager::get_config_path() {
+            Ok(config_path) if Path::new(provider).is_relative() && provider.contains(['/', '\\']) => {
+                config_path.parent().map(|dir| dir.join(provider)).unwrap_or_else(|| PathBuf::from(provider))
+            }
+            _ => PathBuf::from(provider),
+        };
+        let child = Command::new(&program)
+            .current_dir(&context.info.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(e) => {
+                eprintln!("⚠️ template_provider の起動に失敗しました ({}): {}", provider, e);
+                return Vec::new();
+            }
+        };
+
+        // 大きな入力で標準出力のパイプが詰まらないよう、書き込みは別スレッドで行う
+        let writer = child.stdin.take().map(|mut stdin| {
+            std::thread::spawn(move || {
+                // 入力を読まずに終了するプロバイダもあるため書き込みエラーは無視する
+                let _ = stdin.write_all(&input);
+            })
+        });
+        let reader = child.stdout.take().map(|mut stdout| {
+            std::thread::spawn(move || {
+                let mut output = Vec::new();
+                let _ = stdout.read_to_end(&mut output);
+                output
+            })
+        });
+
+        let started = Instant::now();
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break Ok(status),
+                Ok(None) if started.elapsed() >= PROVIDER_TIMEOUT => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    // 子プロセスが起動したプロセスがパイプを開いたままの場合があるため、入出力のスレッドは待たない
+                    eprintln!(
+                        "⚠️ template_provider が{}秒以内に終了しなかったため中止しました ({})",
+                        PROVIDER_TIMEOUT.as_secs(),
+                        provider
+                    );
+                    return Vec::new();
+                }
+                Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+                Err(e) => break Err(e.to_string()),
+            }
+        };
+        if let Some(writer) = writer {
+            let _ = writer.join();
+        }
+        let stdout = reader.and_then(|reader| reader.join().ok()).unwrap_or_default();
+
+        match status {
+            Ok(status) if status.success() => {}
+            Ok(status) => {
+                eprintln!("⚠️ template_provider が失敗しました ({}): {}", provider, status);
+                return Vec::new();
+            }
+            Err(e) => {
+                eprintln!("⚠️ template_provider の実行に失敗しました ({}): {}", provider, e);
+                return Vec::new();
+            }
+        }
+
+        let templates: Vec<ProvidedTemplate> = match serde_json::from_slice(&stdout) {
+            Ok(templates) => templates,
+            Err(e) => {
+                eprintln!("⚠️ template_provider の出力を解析できませんでした（[{{\"name\": ..., \"body\": ...}}] の配列を出力してください）: {}", e);
+                return Vec::new();
+            }
+        };
+
         templates
+            .into_iter()
+            .filter(|t| {
+                let valid = !t.name.is_empty() && !t.name.contains(['/', '\\']) && t.name != "." && t.name != "..";
+                if !valid {
+                    eprintln!("⚠️ template_provider が出力したテンプレート名が不正なため無視します: {:?}", t.name);
+                }
+                valid
+            })
+            .map(|t| (t.name, t.body))
+            .collect()
+    }
+
+    /// `init --no-context`用。検出した種別の組み込み・カスタムテンプレートを本文のまま返す
+    ///
+    /// プロジェクト固有の提案はプロジェクト情報から作るため含めない
+    fn create_raw_init_templates(context: &ProjectContext) -> Result<Vec<(String, String)>, CcmgenError> {
+        let mut templates = TemplateSet::new(ConfigManager::load_config().conflict_policy);
+        templates.add(
+            TemplateSource::Builtin,
+            crate::templates::TemplateManager::get_templates_for_project(&context.info)
+                .into_iter()
+                .map(|(name, content)| (name.to_string(), content.to_string())),
+        );
+
+        let project_type = format!("{:?}", context.info.project_type);
+        let custom_templates = Self::render_custom_templates(context.info.project_type.language_key())
+            .into_iter()
+            .filter(|(_, project_type_filter, _)| project_type_filter.as_ref().is_none_or(|t| *t == project_type))
+            .map(|(name, _, content)| (name, content));
+        templates.add(TemplateSource::Custom, custom_templates);
+
+        if let Some(project_config) = &context.project_config {
+            let project_templates = project_config
+                .templates
+                .iter()
+                .map(|t| (t.name.clone(), t.content.clone()));
+            templates.add(TemplateSource::Project, project_templates);
+        }
+
+        templates.into_templates()
+    }
+
+    /// ソース・テストファイルが多い上位のディレクトリ（大規模プロジェクトの設計レビュー用）
+    fn build_module_layout(context: &ProjectContext) -> String {
+        let root = &context.info.path;
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for file in context.structure.source_files.iter().chain(&context.structure.test_files) {
+            let relative = file.strip_prefix(root).unwrap_or(file);
+            // `src/`直下はその下の階層までをひとまとまりとする
+            let components: Vec<_> = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect();
+            let area = match components.as_slice() {
+                [first, second, _, ..] if first == "src" => format!("src/{}", second),
+                [first, _, ..] => first.to_string(),
+                _ => ".".to_string(),
+            };
+            *counts.entry(area).or_default() += 1;
+        }
+
+        let mut areas: Vec<_> = counts.into_iter().collect();
+        areas.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then_with(|| a_name.cmp(b_name)));
+        let mut lines = vec!["Largest areas:".to_string()];
+        lines.extend(areas.iter().take(MODULE_LAYOUT_LIMIT).map(|(area, files)| format!("- {}: {} files", area, files)));
+        lines.join("\n")
+    }
+
+    /// テストの置き場所（`tests/`の統合テスト・インラインの`#[cfg(test)]`など）と代表的なテストファイルの説明
+    fn build_test_layout(context: &ProjectContext) -> String {
+        let root = &context.info.path;
+        let locations = context.structure.test_locations(root);
+        let mut lines = vec!["Test layout:".to_string()];
+
+        for location in &locations {
+            let directory = location.directory.display().to_string();
+            let kind = match (location.dedicated, directory.as_str()) {
+                (true, "tests") if context.info.project_type == ProjectType::RustNormal => "integration tests",
+                (true, _) => "test directory",
+                (false, "") => "test files in the project root",
+                (false, _) => "test files next to the source",
+            };
+            let directory = if directory.is_empty() { ".".to_string() } else { format!("{}/", directory) };
+            lines.push(format!("- {}: {} ({} files)", directory, kind, location.count));
+        }
+
+        let inline = &context.structure.inline_test_files;
+        if !inline.is_empty() {
+            let mut inline: Vec<_> = inline.iter().map(|f| f.strip_prefix(root).unwrap_or(f)).collect();
+            inline.sort();
+            lines.push(format!(
+                "- inline #[cfg(test)] modules: {} source files (e.g. {})",
+                inline.len(),
+                inline[0].display()
+            ));
+        }
+
+        if let Some(location) = locations.first() {
+            lines.push(format!("Example test: {}", location.example.display()));
+        }
+        lines.join("\n")
+    }
+
+    /// Pythonのツールを実行するときの前置き（`poetry run `など。パッケージマネージャが無ければ空）
+    fn python_runner(context: &ProjectContext) -> &'static str {
+        let has = |tool: &str| context.info.features.iter().any(|f| f == tool);
+        if has("poetry") {
+            "poetry run "
+        } else if has("hatch") {
+            "hatch run "
+        } else if has("pdm") {
+            "pdm run "
+        } else {
+            ""
+        }
+    }
+
+    /// `examples/`・`benches/`のファイル一覧（プロジェクトルートからの相対パス、最大5件）
+    fn build_file_listing(label: &str, files: &[PathBuf], context: &ProjectContext) -> String {
+        let root = &context.info.path;
+        let mut files: Vec<_> = files.iter().map(|f| f.strip_prefix(root).unwrap_or(f)).collect();
+        files.sort();
+        let mut lines = vec![format!("{} ({} files):", label, files.len())];
+        lines.extend(files.iter().take(5).map(|f| format!("- {}", f.display())));
+        if files.len() > 5 {
+            lines.push(format!("- ... and {} more", files.len() - 5));
+        }
+        lines.join("\n")
+    }
+
+    /// 検出したコンテナ関連の設定ファイルの一覧
+    fn container_tooling(setup: &ContainerSetup, context: &ProjectContext) -> String {
+        let root = &context.info.path;
+        let list = |files: &[PathBuf]| {
+            let mut names: Vec<String> = files
+                .iter()
+                .take(10)
+                .map(|f| {
+                    let relative = f.strip_prefix(root).unwrap_or(f).display().to_string();
+                    if setup.multi_stage.contains(f) { format!("{} (multi-stage)", relative) } else { relative }
+                })
+                .collect();
+            if files.len() > 10 {
+                names.push(format!("... and {} more", files.len() - 10));
+            }
+            names.join(", ")
+        };
+        let mut lines = vec!["Container tooling in this project:".to_string()];
+        if !setup.dockerfiles.is_empty() {
+            lines.push(format!("- Dockerfiles: {}", list(&setup.dockerfiles)));
+        }
+        if !setup.compose_files.is_empty() {
+            lines.push(format!("- Docker Compose: {}", list(&setup.compose_files)));
+        }
+        if let Some(devcontainer) = &setup.devcontainer {
+            lines.push(format!("- Dev Container: {}", list(std::slice::from_ref(devcontainer))));
+        }
+        if !setup.k8s_manifests.is_empty() {
+            lines.push(format!("- Kubernetes manifests: {}", list(&setup.k8s_manifests)));
+        }
+        lines.join("\n")
+    }
+
+    /// 既存のベンチマークから使われているハーネスを推定
+    fn bench_harness(files: &[PathBuf]) -> &'static str {
+        let contents: Vec<String> = files.iter().filter_map(|f| fs::read_to_string(f).ok()).collect();
+        let uses = |needle: &str| contents.iter().any(|c| c.contains(needle));
+        if uses("criterion") {
+            "criterion (criterion_group!/criterion_main!)"
+        } else if uses("divan") {
+            "divan (#[divan::bench])"
+        } else if uses("#![feature(test)]") {
+            "libtest #[bench] (nightly)"
+        } else {
+            "unknown (follow the existing benchmarks)"
+        }
+    }
+
+    /// テンプレートエンジンに渡すコンテキストを構築
+    ///
+    /// `info`/`structure`/`suggested_commands`に加えて、よく使う値をトップレベルにも公開する
+    fn build_template_context(context: &ProjectContext) -> tera::Context {
+        let mut ctx = tera::Context::from_serialize(context).unwrap_or_default();
+
+        let mut dependencies: Vec<_> = context.structure.dependencies.iter().collect();
+        dependencies.sort_by_key(|(name, _)| name.as_str());
+        let dependencies: Vec<_> = dependencies
+            .into_iter()
+            .map(|(name, version)| serde_json::json!({ "name": name, "version": version }))
+            .collect();
+
+        ctx.insert("name", &context.info.name);
+        ctx.insert("project_type", &format!("{:?}", context.info.project_type));
+        ctx.insert("features", &context.info.features);
+        ctx.insert("rust_edition", &context.info.rust_edition);
+        ctx.insert("rust_version", &context.info.rust_version);
+        ctx.insert("dependencies", &dependencies);
+        ctx.insert("scripts", &context.structure.scripts);
+        for (key, value) in TEMPLATE_VARS.get().into_iter().flatten() {
+            ctx.insert(key, value);
+        }
+        ctx
+    }
+
+    /// テンプレート本文をTeraで描画。構文エラー時は警告して元の本文を使う
+    fn render_template(name: &str, content: &str, template_context: &tera::Context) -> String {
+        match tera::Tera::one_off(content, template_context, false) {
+            Ok(rendered) => rendered,
+            Err(e) => {
+                eprintln!("⚠️ テンプレートの描画に失敗しました: {}: {}", name, e);
+                content.to_string()
+            }
+        }
+    }
+
+    /// 設定ファイルのカスタムテンプレートを環境変数展開して返す
+    ///
+    /// 戻り値は (名前, project_type指定, 本文)
+    pub fn render_custom_templates(language: &str) -> Vec<(String, Option<String>, String)> {
+        ConfigManager::get_custom_templates_for_language(language)
+            .into_iter()
+            .map(|t| {
+                let metadata = TemplateMetadata { author: t.author, version: t.version };
+                let content = format!("{}{}", metadata.to_frontmatter(), Self::expand_env_vars(&t.content));
+                (t.name, t.project_type, content)
+            })
+            .collect()
+    }
+
+    /// プロジェクト情報を本文の前に付ける。フロントマターがあればその後ろに入れる
+    fn with_context(context: &str, content: &str) -> String {
+        match parse_frontmatter(content) {
+            (Some(frontmatter), body) => format!("---\n{}\n---\n\n{}\n\n{}", frontmatter, context, body),
+            (None, body) => format!("{}\n\n{}", context, body),
+        }
+    }
+
+    /// `${VAR}` をプロセスの環境変数で展開する。未設定の変数は空文字にして警告する
+    fn expand_env_vars(content: &str) -> String {
+        let mut result = String::with_capacity(content.len());
+        let mut rest = content;
+
+        while let Some(start) = rest.find("${") {
+            let Some(len) = rest[start + 2..].find('}') else {
+                break;
+            };
+            result.push_str(&rest[..start]);
+            let var = &rest[start + 2..start + 2 + len];
+            match std::env::var(var) {
+                Ok(value) => result.push_str(&value),
+                Err(_) => eprintln!("⚠️ 環境変数が設定されていません: {}", var),
+            }
+            rest = &rest[start + 2 + len + 1..];
+        }
+        result.push_str(rest);
+
+        result
     }
 }
\ No newline at end of file
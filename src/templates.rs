@@ -1,3 +1,4 @@
+use crate::config::{CommitStyle, ConfigManager};
 use crate::project::{ProjectType, ProjectInfo};
 use std::collections::HashMap;
 
@@ -5,13 +6,65 @@ pub struct TemplateManager;
 
 impl TemplateManager {
     pub fn get_templates_for_project(project: &ProjectInfo) -> Vec<(&'static str, &'static str)> {
-        match project.project_type {
+        Self::get_templates_for_type(&project.project_type)
+    }
+
+    pub fn get_templates_for_type(project_type: &ProjectType) -> Vec<(&'static str, &'static str)> {
+        let mut templates = match project_type {
             ProjectType::RustNormal => Self::rust_templates(),
             ProjectType::RustWasm => Self::rust_wasm_templates(),
             ProjectType::JavaScript => Self::javascript_templates(),
             ProjectType::TypeScript => Self::typescript_templates(),
             ProjectType::NodeJs => Self::nodejs_templates(),
+            ProjectType::Bun => Self::bun_templates(),
+            ProjectType::Unity => Self::unity_templates(),
+            ProjectType::R => Self::r_templates(),
+            ProjectType::Haskell => Self::haskell_templates(),
+            ProjectType::Scala => Self::scala_templates(),
+            ProjectType::Python => Self::python_templates(),
+        };
+        templates.extend(Self::universal_templates());
+        templates
+    }
+
+    /// 全プロジェクト種別の組み込みテンプレート（同名は先に定義された方を採用）
+    pub fn all_builtin_templates() -> Vec<(&'static str, &'static str)> {
+        let mut templates: Vec<(&'static str, &'static str)> = Vec::new();
+        let all = [
+            Self::rust_templates(),
+            Self::rust_wasm_templates(),
+            Self::javascript_templates(),
+            Self::typescript_templates(),
+            Self::nodejs_templates(),
+            Self::bun_templates(),
+            Self::unity_templates(),
+            Self::r_templates(),
+            Self::haskell_templates(),
+            Self::scala_templates(),
+            Self::python_templates(),
+            Self::universal_templates(),
+        ];
+        for (name, content) in all.into_iter().flatten() {
+            if !templates.iter().any(|(n, _)| *n == name) {
+                templates.push((name, content));
+            }
         }
+        templates
+    }
+
+    /// 全てのプロジェクト種別で共通のテンプレート
+    fn universal_templates() -> Vec<(&'static str, &'static str)> {
+        let commit_style = ConfigManager::load_config().default_settings.commit_style;
+        let commit_message = match commit_style {
+            CommitStyle::Conventional =>
+                "Generate a Conventional Commits style message (type(scope): subject, followed by a body explaining what and why) for the following staged changes:",
+            CommitStyle::Plain =>
+                "Generate a concise commit message (a short imperative subject line, followed by a body explaining what and why) for the following staged changes:",
+        };
+
+        vec![
+            ("generate-commit-message", commit_message),
+        ]
     }
 
     fn rust_templates() -> Vec<(&'static str, &'static str)> {
@@ -121,13 +174,89 @@ impl TemplateManager {
         ]
     }
 
+    fn bun_templates() -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("convert-to-bun-test", 
+             "Convert these Jest/Vitest tests to use bun:test and run them with `bun test`:"),
+            ("add-bun-http-server", 
+             "Implement an HTTP server for this functionality using Bun.serve:"),
+            ("use-bun-apis", 
+             "Replace Node.js APIs in this code with native Bun APIs (Bun.file, Bun.write, Bun.spawn):"),
+            ("bunfig-optimization", 
+             "Optimize bunfig.toml and package.json settings for this Bun project:"),
+            ("generate-tests", 
+             "Generate unit tests for the following function using bun:test:"),
+        ]
+    }
+
+    fn unity_templates() -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("optimize-update-loop", 
+             "Review the Update/FixedUpdate/LateUpdate methods in this Unity C# script and move work out of per-frame loops (caching components, using events or coroutines) where possible:"),
+            ("review-garbage-allocation", 
+             "Find per-frame managed heap allocations (boxing, LINQ, string concatenation, closures, GetComponent calls) in this Unity C# code and suggest allocation-free alternatives:"),
+            ("add-scriptableobject", 
+             "Extract the configuration data in this Unity C# code into a ScriptableObject with a [CreateAssetMenu] attribute:"),
+            ("generate-tests", 
+             "Generate Edit Mode tests for the following Unity C# code using the Unity Test Framework (NUnit):"),
+            ("add-documentation", 
+             "Add XML documentation comments (///) to the following Unity C# code:"),
+        ]
+    }
+
+    fn r_templates() -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("generate-testthat", 
+             "Generate testthat unit tests (tests/testthat/test-*.R) for the following R function, covering edge cases such as NA, empty vectors, and invalid input:"),
+            ("add-roxygen-docs", 
+             "Add roxygen2 documentation (#' @title, @param, @return, @examples, @export) to the following R function:"),
+            ("vectorize-loop", 
+             "Rewrite the loops in this R code using vectorized operations or the apply family (vapply, lapply, purrr) and explain the performance difference:"),
+        ]
+    }
+
+    fn haskell_templates() -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("generate-hspec", 
+             "Generate Hspec tests (with QuickCheck properties where a law or invariant applies) for the following Haskell function:"),
+            ("add-type-signatures", 
+             "Add explicit top-level type signatures to the following Haskell code, generalizing to the most useful type class constraints:"),
+            ("review-laziness", 
+             "Review this Haskell code for space leaks caused by laziness (lazy accumulators, foldl, unevaluated thunks in records) and suggest strictness annotations, foldl', or strict data types:"),
+        ]
+    }
+
+    fn scala_templates() -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("generate-scalatest", 
+             "Generate ScalaTest specs (AnyFlatSpec with Matchers, plus ScalaCheck property checks where an invariant applies) for the following Scala code:"),
+            ("add-cats-effect", 
+             "Refactor the following Scala code to Cats Effect: model side effects with IO, manage resources with Resource, and replace Futures and blocking calls with the appropriate IO constructors:"),
+            ("review-implicits", 
+             "Review the implicits (or Scala 3 givens/using clauses and extension methods) in this Scala code for ambiguity, surprising conversions, and resolution that is hard to follow, and suggest clearer alternatives:"),
+        ]
+    }
+
+    fn python_templates() -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("generate-pytest", 
+             "Generate pytest tests for the following Python code, using fixtures for shared setup and @pytest.mark.parametrize for input variations:"),
+            ("add-type-hints", 
+             "Add precise type hints (PEP 484/604 syntax, Protocols and TypedDicts where appropriate, avoiding Any) to the following Python code:"),
+            ("add-docstrings", 
+             "Add docstrings to the following Python code, following the docstring style already used in the project (Google, NumPy or reStructuredText):"),
+        ]
+    }
+
+    #[allow(dead_code)]
     pub fn get_custom_templates() -> HashMap<String, String> {
         // Future: Load from configuration file
         HashMap::new()
     }
 
+    #[allow(dead_code)]
     pub fn create_project_specific_template(project: &ProjectInfo, _template_name: &str, content: &str) -> String {
-        let context = format!("Project: {} ({})", project.name, format!("{:?}", project.project_type));
+        let context = format!("Project: {} ({:?})", project.name, project.project_type);
         let features = if !project.features.is_empty() {
             format!("Features: {}", project.features.join(", "))
         } else {